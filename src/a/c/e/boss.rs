@@ -0,0 +1,31 @@
+use super::mon::MonsterType;
+use super::spell::Spell;
+use super::status::Status;
+use super::Style;
+
+// Bosses are as much tougher than a monster of their kind as this.
+pub const BOSS_HP: u32 = 3;
+
+// The monsters waiting at the bottom of a dungeon.
+pub const CAPSTONES: [MonsterType; 3] = [MonsterType::Dragon, MonsterType::VoidLord, MonsterType::Archon];
+
+// What a boss turns into once its hp drops below `below` percent of its max hp.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Phase {
+    pub below: u32,
+    pub shifts: Vec<PhaseShift>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum PhaseShift {
+    Abilities(Vec<Spell>),       // replaces whatever it fought with before
+    Status(Status, u16, u16),    // status, value, duration
+    Summon(MonsterType, u8, u8), // kind, level, how many answer the call
+    Style(Style),
+}
+
+// The phases a boss of this kind goes through, in order, as the monster file lists them.
+// Kinds without any fight the same to the end.
+pub fn phases(kind: &MonsterType) -> &'static [Phase] {
+    &kind.definition().phases
+}
@@ -60,10 +60,18 @@ impl Style {
     }
 }
 
+// How quick wizards and monsters are unless told otherwise; in battle, Air affinity and
+// quickening statuses come on top of it.
+pub const BASE_SPEED: u16 = 10;
+
 pub mod status;
 pub mod mon;
+pub mod boss;
+pub mod monster_registry;
 pub mod spell;
+pub mod spell_registry;
 pub mod spell_book;
 pub mod wiz;
 pub mod party;
+pub mod reaction;
 
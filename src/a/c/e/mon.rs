@@ -1,11 +1,11 @@
-use super::spell::Spell;
-use super::wiz::{Acceptance, Affinity};
+use super::boss::{self, Phase, PhaseShift};
+use super::monster_registry::{MonsterDefinition, MonsterRegistry};
+use super::spell::{spells, Spell};
+use super::wiz::{Acceptance, Affinity, MindSet};
 use super::status::StatusSet;
 use super::{Style, Glyph};
 use crate::generational_arena::Index;
 use lazy_static::lazy_static;
-use map_macro::hash_map;
-use std::collections::HashMap;
 
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub enum MonsterType {
@@ -41,6 +41,8 @@ pub enum MonsterType {
     Archon,
     FallenAngel,
     Angel,
+    // Defined in the monster file rather than here.
+    Custom(&'static str),
 }
 
 pub const ALL: [MonsterType; 32] = [
@@ -78,123 +80,78 @@ pub const ALL: [MonsterType; 32] = [
     MonsterType::Angel,
 ];
 
+pub const MONSTERS_FILENAME: &str = "./assets/monsters.txt";
+
+// Written in place of a type id for monsters that only exist in the monster
+// file, followed by the monster's name.
+pub const CUSTOM: u8 = 255;
+
+// The monster file as it was when the game was built, for when there is none next to it.
+const BUILT_IN: &str = include_str!("../../../../assets/monsters.txt");
+
 lazy_static! {
-    pub static ref BY_NAME: HashMap<&'static str, MonsterType> = hash_map! {
-        "Dragon" => MonsterType::Dragon,
-        "Demon" => MonsterType::Demon,
-        "Elemental" => MonsterType::Elemental,
-        "Ogre" => MonsterType::Ogre,
-        "Goblin" => MonsterType::Goblin,
-        "Orc" => MonsterType::Orc,
-        "Human" => MonsterType::Human,
-        "Hellcat" => MonsterType::Hellcat,
-        "Rat" => MonsterType::Rat,
-        "Slime" => MonsterType::Slime,
-        "Spider" => MonsterType::Spider,
-        "Snake" => MonsterType::Snake,
-        "DireWolf" => MonsterType::DireWolf,
-        "Wolf" => MonsterType::Wolf,
-        "Bear" => MonsterType::Bear,
-        "Bat" => MonsterType::Bat,
-        "AncientConstruct" => MonsterType::AncientConstruct,
-        "Construct" => MonsterType::Construct,
-        "UndeadHuman" => MonsterType::UndeadHuman,
-        "UndeadOrc" => MonsterType::UndeadOrc,
-        "UndeadGoblin" => MonsterType::UndeadGoblin,
-        "UndeadTroll" => MonsterType::UndeadTroll,
-        "Guardian" => MonsterType::Guardian,
-        "Voidling" => MonsterType::Voidling,
-        "VoidSpawn" => MonsterType::VoidSpawn,
-        "VoidWalker" => MonsterType::VoidWalker,
-        "VoidLord" => MonsterType::VoidLord,
-        "Templar" => MonsterType::Templar,
-        "Archon" => MonsterType::Archon,
-        "FallenAngel" => MonsterType::FallenAngel,
-        "Angel" => MonsterType::Angel,
-    };
+    static ref REGISTRY: std::result::Result<MonsterRegistry, String> = read().map_err(|e| e.to_string());
+}
+
+// Every kind the game knows by name has to be there, custom ones may be added.
+fn read() -> std::io::Result<MonsterRegistry> {
+    let spells = spells::load()?;
+    let mut registry = MonsterRegistry::new();
+    if std::path::Path::new(MONSTERS_FILENAME).exists() {
+        registry.load(MONSTERS_FILENAME, spells)?;
+    } else {
+        registry.parse(BUILT_IN, MONSTERS_FILENAME, spells)?;
+    }
+    match ALL.iter().find(|kind| registry.get(kind).is_none()) {
+        Some(kind) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{}: monster `{:?}` is not defined", MONSTERS_FILENAME, kind),
+        )),
+        None => Ok(registry),
+    }
+}
+
+// Reads the monsters, and the spells they may refer to, the first time it is called.
+// Call it before anything else needs a monster to hear about a broken monster file.
+pub fn load() -> std::io::Result<&'static MonsterRegistry> {
+    REGISTRY.as_ref().map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.clone()))
+}
+
+pub fn registry() -> &'static MonsterRegistry {
+    load().unwrap_or_else(|e| panic!("Failed to load monsters: {}", e))
 }
 
 impl MonsterType {
     pub fn ability_with_style(monster_type: MonsterType, style: &Style) -> Vec<Spell> {
-        let mut abilities = abilities::BY_TYPE.get(&monster_type).unwrap().clone();
+        let mut abilities = monster_type.definition().abilities.clone();
         abilities.iter_mut().for_each(|spell| {
             spell.style = (style.clone(), spell.style.1);
         });
         abilities
     }
 
+    pub fn definition(&self) -> &'static MonsterDefinition {
+        registry().get(self).unwrap_or_else(|| panic!("Unknown monster type: {:?}", self))
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.definition().name
+    }
+
     pub fn difficulty(&self) -> u8 {
-        match self {
-            Self::Troll => 4,
-            Self::Bear => 5,
-            Self::Bat => 1,
-            Self::Spider => 1,
-            Self::Snake => 2,
-            Self::Wolf => 2,
-            Self::DireWolf => 3,
-            Self::Hellcat => 3,
-            Self::Demon => 5,
-            Self::Dragon => 6,
-            Self::Ogre => 4,
-            Self::Goblin => 1,
-            Self::Orc => 2,
-            Self::UndeadGoblin => 1,
-            Self::UndeadHuman => 2,
-            Self::UndeadOrc => 2,
-            Self::UndeadTroll => 3,
-            Self::AncientConstruct => 4,
-            Self::Angel => 5,
-            Self::Archon => 6,
-            Self::Templar => 5,
-            Self::Elemental => 4,
-            Self::Guardian => 5,
-            Self::Rat => 1,
-            Self::Slime => 1,
-            Self::Voidling => 2,
-            Self::VoidWalker => 4,
-            Self::VoidSpawn => 3,
-            Self::VoidLord => 6,
-            Self::FallenAngel => 5,
-            Self::Human => 2,
-            Self::Construct => 3,
-        }
+        self.definition().difficulty
     }
 
     pub fn hp(&self, difficulty: u32) -> u32 {
-        difficulty * match self {
-            Self::Troll => 20,
-            Self::Bear => 25,
-            Self::Bat => 10,
-            Self::Spider => 10,
-            Self::Snake => 15,
-            Self::Wolf => 15,
-            Self::DireWolf => 20,
-            Self::Hellcat => 20,
-            Self::Demon => 25,
-            Self::Dragon => 30,
-            Self::Ogre => 20,
-            Self::Goblin => 10,
-            Self::Orc => 15,
-            Self::UndeadGoblin => 10,
-            Self::UndeadHuman => 15,
-            Self::UndeadOrc => 15,
-            Self::UndeadTroll => 20,
-            Self::AncientConstruct => 20,
-            Self::Angel => 25,
-            Self::Archon => 30,
-            Self::Templar => 25,
-            Self::Elemental => 20,
-            Self::Guardian => 25,
-            Self::Rat => 10,
-            Self::Slime => 10,
-            Self::Voidling => 15,
-            Self::VoidWalker => 20,
-            Self::VoidSpawn => 20,
-            Self::VoidLord => 30,
-            Self::FallenAngel => 25,
-            Self::Human => 15,
-            Self::Construct => 20,
-        }
+        self.definition().hp(difficulty)
+    }
+
+    pub fn speed(&self) -> u16 {
+        self.definition().speed
+    }
+
+    pub fn mindset(&self) -> MindSet {
+        self.definition().mindset.clone()
     }
 
     pub fn as_u8(&self) -> u8 {
@@ -231,9 +188,19 @@ impl MonsterType {
             Self::FallenAngel => 29,
             Self::Human => 30,
             Self::Construct => 31,
+            Self::Custom(_) => CUSTOM,
         }
     }
 
+    pub fn from_name(name: &str) -> Option<MonsterType> {
+        registry().by_name(name).map(|monster| monster.kind.clone())
+    }
+
+    // The types the game itself knows, by the name they go by in the monster file.
+    pub fn built_in(name: &str) -> Option<MonsterType> {
+        ALL.iter().find(|kind| format!("{:?}", kind).eq_ignore_ascii_case(name)).cloned()
+    }
+
     pub fn from_u8(kind: u8) -> MonsterType {
         match kind {
             0 => Self::Troll,
@@ -272,719 +239,113 @@ impl MonsterType {
         }
     }
 }
-pub mod abilities {
-    use super::super::{spell::{
-        Ability, Effect, EffectApplication, EffectDuration, PriorityType, Spell, spells,
-        TargetType,EffectProgression, PriorityTypes
-    }, status::Status};
-    use super::super::{Glyph, Style};
-    use super::MonsterType;
-    use lazy_static::lazy_static;
-    use map_macro::hash_map;
-    use std::collections::HashMap;
-
-    lazy_static! {
-        pub static ref BY_TYPE: HashMap<MonsterType, Vec<Spell>> = hash_map! {
-            MonsterType::Troll => TROLL.to_vec(),
-            MonsterType::Bear => BEAR.to_vec(),
-            MonsterType::Bat => BAT.to_vec(),
-            MonsterType::Spider => SPIDER.to_vec(),
-            MonsterType::Snake => SNAKE.to_vec(),
-            MonsterType::Wolf => WOLF.to_vec(),
-            MonsterType::DireWolf => DIRE_WOLF.to_vec(),
-            MonsterType::Hellcat => HELLCAT.to_vec(),
-            MonsterType::Demon => DEMON.to_vec(),
-            MonsterType::Dragon => DRAGON.to_vec(),
-            MonsterType::Ogre => OGRE.to_vec(),
-            MonsterType::Goblin => GOBLIN.to_vec(),
-            MonsterType::Orc => ORC.to_vec(),
-            MonsterType::UndeadGoblin => UNDEAD_GOBLIN.to_vec(),
-            MonsterType::UndeadHuman => UNDEAD_HUMAN.to_vec(),
-            MonsterType::UndeadOrc => UNDEAD_ORC.to_vec(),
-            MonsterType::UndeadTroll => UNDEAD_TROLL.to_vec(),
-            MonsterType::AncientConstruct => ANCIENT_CONSTRUCT.to_vec(),
-            MonsterType::Angel => ANGEL.to_vec(),
-            MonsterType::Archon => ARCHON.to_vec(),
-            MonsterType::Templar => TEMPLAR.to_vec(),
-            MonsterType::Elemental => ELEMENTAL.to_vec(),
-            MonsterType::Guardian => GUARDIAN.to_vec(),
-            MonsterType::Rat => RAT.to_vec(),
-            MonsterType::Slime => SLIME.to_vec(),
-            MonsterType::Voidling => VOIDLING.to_vec(),
-            MonsterType::VoidWalker => VOID_WALKER.to_vec(),
-            MonsterType::VoidSpawn => VOID_SPAWN.to_vec(),
-            MonsterType::VoidLord => VOID_LORD.to_vec(),
-            MonsterType::FallenAngel => FALLEN_ANGEL.to_vec(),
-            MonsterType::Human => HUMAN.to_vec(),
-            MonsterType::Construct => CONSTRUCT.to_vec(),
-        };
-    }
-
-    pub const TROLL: [Spell; 2] = [
-        Spell {
-            name: "Bash",
-            glyph: (Glyph::Earth, 1),
-            style: (Style::Void, 1),
-            ability: Ability::duo(
-                PriorityType::LowHealth,
-                TargetType::Enemy(2),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-                Effect::new(
-                    1,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Stunned, 1),
-                ),
-            ),
-        },
-        Spell {
-            name: "Bite",
-            glyph: (Glyph::Earth, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Enemy(1),
-                Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-    ];
-
-    pub const BEAR: [Spell; 2] = [
-        Spell {
-            name: "Bite",
-            glyph: (Glyph::Earth, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Enemy(1),
-                Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Swipe",
-            glyph: (Glyph::Earth, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Enemy(3),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-    ];
-
-    pub const BAT: [Spell; 1] = [Spell {
-        name: "Bite",
-        glyph: (Glyph::Air, 1),
-        style: (Style::Void, 1),
-        ability: Ability::single(
-            PriorityType::LowHealth,
-            TargetType::Enemy(1),
-            Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-        ),
-    }];
-
-    pub const SPIDER: [Spell; 1] = [Spell {
-        name: "Bite",
-        glyph: (Glyph::Earth, 1),
-        style: (Style::Void, 1),
-        ability: Ability::single(
-            PriorityType::LowHealth,
-            TargetType::Enemy(1),
-            Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-        ),
-    }];
-
-    pub const SNAKE: [Spell; 2] = [
-        Spell {
-            name: "Bite",
-            glyph: (Glyph::Fire, 1),
-            style: (Style::Void, 1),
-            ability: Ability::duo(
-                PriorityType::Tanky,
-                TargetType::Enemy(1),
-                Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-                Effect::new(
-                    1,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Burning, 3),
-                ),
-            ),
-        },
-        Spell {
-            name: "Constrict",
-            glyph: (Glyph::Air, 1),
-            style: (Style::Void, 1),
-            ability: Ability::duo(
-                PriorityType::Squishy,
-                TargetType::Enemy(1),
-                Effect::new(3, EffectDuration::Growth(3, 1), EffectApplication::Damage),
-                Effect::new(
-                    1,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Weakened, 1),
-                ),
-            ),
-        },
-    ];
-
-    pub const WOLF: [Spell; 2] = [
-        Spell {
-            name: "Bite",
-            glyph: (Glyph::Earth, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Enemy(1),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Howl",
-            glyph: (Glyph::Air, 1),
-            style: (Style::Void, 1),
-            ability: Ability::exact(
-                PriorityTypes::Or(
-                    PriorityType::NoStatus(Status::Raging),
-                    PriorityType::HasStatus(Status::Weakened),
-                ),
-                TargetType::Ally(3),
-                EffectProgression::Single(Effect::new(
-                    3,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Raging, 1),
-                )),
-            ),
-        },
-    ];
-
-    pub const DIRE_WOLF: [Spell; 3] = [
-        Spell {
-            name: "Bite",
-            glyph: (Glyph::Earth, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Enemy(1),
-                Effect::new(10, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Howl",
-            glyph: (Glyph::Air, 1),
-            style: (Style::Void, 1),
-            ability: Ability::exact(
-                PriorityTypes::Or(
-                    PriorityType::NoStatus(Status::Raging),
-                    PriorityType::HasStatus(Status::Weakened),
-                ),
-                TargetType::Ally(3),
-                EffectProgression::Single(Effect::new(
-                    5,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Raging, 1),
-                )),
-            ),
-        },
-        Spell {
-            name: "Swipe",
-            glyph: (Glyph::Earth, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Enemy(3),
-                Effect::new(4, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-    ];
-
-    pub const HELLCAT: [Spell; 2] = [
-        Spell {
-            name: "Bite",
-            glyph: (Glyph::Fire, 1),
-            style: (Style::Void, 1),
-            ability: Ability::duo(
-                PriorityType::LowHealth,
-                TargetType::Enemy(1),
-                Effect::new(7, EffectDuration::Instant, EffectApplication::Damage),
-                Effect::new(
-                    1,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Burning, 5),
-                ),
-            ),
-        },
-        Spell {
-            name: "Tail whip",
-            glyph: (Glyph::Fire, 1),
-            style: (Style::Void, 1),
-            ability: Ability::duo(
-                PriorityType::LowHealth,
-                TargetType::Enemy(3),
-                Effect::new(4, EffectDuration::Instant, EffectApplication::Damage),
-                Effect::new(
-                    2,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Burning, 3),
-                ),
-            ),
-        },
-    ];
-
-    pub const DEMON: [Spell; 2] = [spells::fire::FIREBALL, spells::fire::FIRESTORM];
-
-    pub const DRAGON: [Spell; 2] = [
-        Spell {
-            name: "Bite",
-            glyph: (Glyph::Fire, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Enemy(1),
-                Effect::new(10, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Fire Breath",
-            glyph: (Glyph::Fire, 3),
-            style: (Style::Void, 1),
-            ability: Ability::duo(
-                PriorityType::NoStatus(Status::Burning),
-                TargetType::Enemy(8),
-                Effect::new(10, EffectDuration::Instant, EffectApplication::Damage),
-                Effect::new(
-                    3,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Burning, 5),
-                ),
-            ),
-        },
-    ];
-
-    pub const OGRE: [Spell; 2] = [
-        Spell {
-            name: "Clobber",
-            glyph: (Glyph::Earth, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Enemy(1),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Smash",
-            glyph: (Glyph::Earth, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::Squishy,
-                TargetType::Enemy(3),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-    ];
-
-    pub const GOBLIN: [Spell; 1] = [Spell {
-        name: "Stab",
-        glyph: (Glyph::Air, 1),
-        style: (Style::Void, 1),
-        ability: Ability::single(
-            PriorityType::Squishy,
-            TargetType::Enemy(1),
-            Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-        ),
-    }];
-
-    pub const ORC: [Spell; 2] = [
-        Spell {
-            name: "Stab",
-            glyph: (Glyph::Air, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::Squishy,
-                TargetType::Enemy(1),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Slash",
-            glyph: (Glyph::Air, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::HighHealth,
-                TargetType::Enemy(1),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-    ];
-
-    pub const UNDEAD_GOBLIN: [Spell; 1] = [Spell {
-        name: "Stab",
-        glyph: (Glyph::Air, 1),
-        style: (Style::Void, 1),
-        ability: Ability::single(
-            PriorityType::Squishy,
-            TargetType::Enemy(1),
-            Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-        ),
-    }];
-
-    pub const UNDEAD_HUMAN: [Spell; 1] = [Spell {
-        name: "Stab",
-        glyph: (Glyph::Air, 1),
-        style: (Style::Void, 1),
-        ability: Ability::single(
-            PriorityType::Squishy,
-            TargetType::Enemy(1),
-            Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-        ),
-    }];
-
-    pub const UNDEAD_ORC: [Spell; 1] = [Spell {
-        name: "Stab",
-        glyph: (Glyph::Air, 1),
-        style: (Style::Void, 1),
-        ability: Ability::single(
-            PriorityType::Squishy,
-            TargetType::Enemy(1),
-            Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-        ),
-    }];
-
-    pub const UNDEAD_TROLL: [Spell; 1] = [Spell {
-        name: "Bash",
-        glyph: (Glyph::Earth, 1),
-        style: (Style::Void, 1),
-        ability: Ability::duo(
-            PriorityType::LowHealth,
-            TargetType::Enemy(2),
-            Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            Effect::new(
-                1,
-                EffectDuration::Instant,
-                EffectApplication::Status(Status::Stunned, 1),
-            ),
-        ),
-    }];
-
-    pub const ANCIENT_CONSTRUCT: [Spell; 2] = [
-        Spell {
-            name: "Beam",
-            glyph: (Glyph::Fire, 2),
-            style: (Style::Void, 1),
-            ability: Ability::duo(
-                PriorityType::LowHealth,
-                TargetType::Enemy(2),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-                Effect::new(
-                    1,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Stunned, 1),
-                ),
-            ),
-        },
-        Spell {
-            name: "Smash",
-            glyph: (Glyph::Earth, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::Squishy,
-                TargetType::Enemy(3),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-    ];
-
-    pub const ANGEL: [Spell; 2] = [
-        Spell {
-            name: "Smite",
-            glyph: (Glyph::Fire, 2),
-            style: (Style::Void, 1),
-            ability: Ability::duo(
-                PriorityType::LowHealth,
-                TargetType::Enemy(1),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-                Effect::new(
-                    1,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Stunned, 1),
-                ),
-            ),
-        },
-        Spell {
-            name: "Heal",
-            glyph: (Glyph::Water, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Ally(1),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Heal),
-            ),
-        },
-    ];
-
-    pub const ARCHON: [Spell; 2] = [
-        Spell {
-            name: "Fire ball",
-            glyph: (Glyph::Fire, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Ally(3),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Water spiral",
-            glyph: (Glyph::Water, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Enemy(3),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-    ];
-
-    pub const TEMPLAR: [Spell; 2] = [
-        Spell {
-            name: "Fire ball",
-            glyph: (Glyph::Fire, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Ally(3),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Water spiral",
-            glyph: (Glyph::Water, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Enemy(3),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-    ];
-
-    pub const ELEMENTAL: [Spell; 4] = [
-        spells::fire::FIREBALL,
-        spells::water::SUBMERGE,
-        spells::earth::EARTHQUAKE,
-        spells::air::LIGHTNING,
-    ];
-
-    pub const GUARDIAN: [Spell; 2] = [
-        Spell {
-            name: "Smash",
-            glyph: (Glyph::Earth, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::Squishy,
-                TargetType::Enemy(3),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Shield",
-            glyph: (Glyph::Earth, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Ally(1),
-                Effect::new(
-                    5,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Barrier(Glyph::Earth), 1),
-                ),
-            ),
-        },
-    ];
-
-    pub const RAT: [Spell; 1] = [Spell {
-        name: "Bite",
-        glyph: (Glyph::Air, 1),
-        style: (Style::Void, 1),
-        ability: Ability::single(
-            PriorityType::Squishy,
-            TargetType::Enemy(1),
-            Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-        ),
-    }];
-
-    pub const SLIME: [Spell; 1] = [Spell {
-        name: "Slap",
-        glyph: (Glyph::Water, 1),
-        style: (Style::Void, 1),
-        ability: Ability::single(
-            PriorityType::Squishy,
-            TargetType::Enemy(1),
-            Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-        ),
-    }];
-
-    pub const VOIDLING: [Spell; 1] = [Spell {
-        name: "Zap",
-        glyph: (Glyph::Void, 1),
-        style: (Style::Void, 1),
-        ability: Ability::single(
-            PriorityType::Squishy,
-            TargetType::Enemy(1),
-            Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-        ),
-    }];
-
-    pub const VOID_SPAWN: [Spell; 2] = [
-        Spell {
-            name: "Spark",
-            glyph: (Glyph::Void, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::Squishy,
-                TargetType::Enemy(2),
-                Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Zap",
-            glyph: (Glyph::Void, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::Squishy,
-                TargetType::Enemy(1),
-                Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-    ];
-
-    pub const VOID_WALKER: [Spell; 2] = [
-        Spell {
-            name: "Zap",
-            glyph: (Glyph::Void, 1),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::Squishy,
-                TargetType::Enemy(1),
-                Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Void Blast",
-            glyph: (Glyph::Void, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::Squishy,
-                TargetType::Enemy(3),
-                Effect::new(
-                    3,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Weakened, 1),
-                ),
-            ),
-        },
-    ];
-
-    pub const VOID_LORD: [Spell; 0] = [];
-
-    pub const FALLEN_ANGEL: [Spell; 2] = [
-        Spell {
-            name: "Smite",
-            glyph: (Glyph::Fire, 2),
-            style: (Style::Void, 1),
-            ability: Ability::duo(
-                PriorityType::LowHealth,
-                TargetType::Enemy(1),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-                Effect::new(
-                    1,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Stunned, 1),
-                ),
-            ),
-        },
-        Spell {
-            name: "Heal",
-            glyph: (Glyph::Water, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Ally(1),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Heal),
-            ),
-        },
-    ];
-
-    pub const HUMAN: [Spell; 1] = [Spell {
-        name: "Stab",
-        glyph: (Glyph::Air, 1),
-        style: (Style::Void, 1),
-        ability: Ability::single(
-            PriorityType::Squishy,
-            TargetType::Enemy(1),
-            Effect::new(3, EffectDuration::Instant, EffectApplication::Damage),
-        ),
-    }];
-
-    pub const CONSTRUCT: [Spell; 2] = [
-        Spell {
-            name: "Smash",
-            glyph: (Glyph::Earth, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::Squishy,
-                TargetType::Enemy(3),
-                Effect::new(5, EffectDuration::Instant, EffectApplication::Damage),
-            ),
-        },
-        Spell {
-            name: "Shield",
-            glyph: (Glyph::Earth, 2),
-            style: (Style::Void, 1),
-            ability: Ability::single(
-                PriorityType::LowHealth,
-                TargetType::Ally(1),
-                Effect::new(
-                    5,
-                    EffectDuration::Instant,
-                    EffectApplication::Status(Status::Barrier(Glyph::Earth), 1),
-                ),
-            ),
-        },
-    ];
-}
+// What a monster gains with every level past the first: affinity for the glyphs it casts in, which
+// it hits and resists with alike, and every few levels more weight behind each of its abilities.
+pub const AFFINITY_PER_LEVEL: u32 = 1;
+pub const LEVELS_PER_GLYPH: u8 = 3;
 
 #[derive(Clone, Debug)]
 pub struct Monster {
     pub id: Option<Index>,
     pub name: String,
     pub monster_type: MonsterType,
+    pub level: u8,
     pub affinity: Affinity,
     pub acceptance: Acceptance,
     pub hp: u32,
     pub max_hp: u32,
+    pub speed: u16,
+    pub state: MindSet,
     pub status: StatusSet,
+    pub phase: Option<u8>, // how many phases a boss has been through, None for everyone else
 }
 
 impl Monster {
     pub fn new(
         name: &str,
         monster_type: &MonsterType,
-        difficulty: u8,
+        level: u8,
     ) -> Self {
+        let definition = monster_type.definition();
+        let mut affinity = definition.affinity.clone();
+        let grown = level.saturating_sub(1) as u32 * AFFINITY_PER_LEVEL;
+        let mut glyphs: Vec<&Glyph> = Vec::new();
+        for ability in definition.abilities_at(level) {
+            if !glyphs.contains(&&ability.glyph.0) {
+                glyphs.push(&ability.glyph.0);
+            }
+        }
+        for glyph in glyphs.into_iter() {
+            match glyph {
+                Glyph::Fire => affinity.fire += grown,
+                Glyph::Water => affinity.water += grown,
+                Glyph::Earth => affinity.earth += grown,
+                Glyph::Air => affinity.air += grown,
+                Glyph::Void => affinity.void += grown,
+            }
+        }
         Self {
             id: None,
             name: name.to_string(),
             monster_type: monster_type.clone(),
-            affinity: Affinity::new(),
-            acceptance: Acceptance::new(),
-            hp: monster_type.hp(difficulty as u32),
-            max_hp: monster_type.hp(difficulty as u32),
+            level,
+            affinity,
+            acceptance: Acceptance::from_style(definition.acceptance.clone(), level as u32),
+            hp: definition.hp(level as u32),
+            max_hp: definition.hp(level as u32),
+            speed: definition.speed,
+            state: definition.mindset.clone(),
             status: StatusSet::new(),
+            phase: None,
         }
     }
 
+    pub fn boss(
+        name: &str,
+        monster_type: &MonsterType,
+        level: u8,
+    ) -> Self {
+        let mut monster = Self::new(name, monster_type, level);
+        monster.hp *= boss::BOSS_HP;
+        monster.max_hp *= boss::BOSS_HP;
+        monster.phase = Some(0);
+        monster
+    }
+
     pub fn get_abilities(&self) -> Vec<Spell> {
-        MonsterType::ability_with_style(self.monster_type.clone(), self.acceptance.get_highest())
+        let swapped = self.passed_phases().iter().rev()
+            .flat_map(|phase| phase.shifts.iter())
+            .find_map(|shift| match shift {
+                PhaseShift::Abilities(spells) => Some(spells),
+                _ => None,
+            });
+        let weight = self.level.saturating_sub(1) / LEVELS_PER_GLYPH;
+        let abilities: Vec<Spell> = match swapped {
+            Some(spells) => spells.to_vec(),
+            None => self.monster_type.definition().abilities_at(self.level).cloned().collect(),
+        };
+        abilities.into_iter()
+            .map(|spell| Spell {
+                glyph: (spell.glyph.0.clone(), spell.glyph.1 + weight as u16),
+                style: (self.acceptance.get_highest().clone(), spell.style.1),
+                ..spell
+            })
+            .collect()
+    }
+
+    // The phase a boss is about to enter, if it has fallen far enough for it.
+    pub fn next_phase(&self) -> Option<&'static Phase> {
+        let phase = boss::phases(&self.monster_type).get(self.phase? as usize)?;
+        if self.hp > 0 && self.hp * 100 < phase.below * self.max_hp {
+            Some(phase)
+        } else {
+            None
+        }
+    }
+
+    fn passed_phases(&self) -> &'static [Phase] {
+        let phases = boss::phases(&self.monster_type);
+        &phases[..(self.phase.unwrap_or(0) as usize).min(phases.len())]
     }
 
     pub fn augment(&self, glyph: &Glyph) -> u16 {
@@ -1002,4 +363,44 @@ impl PartialEq for Monster {
     }
 }
 
-impl Eq for Monster {}
\ No newline at end of file
+impl Eq for Monster {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_built_in_monsters_parse() {
+        let mut monsters = MonsterRegistry::new();
+        monsters.parse(BUILT_IN, "monsters.txt", spells::load().unwrap()).unwrap();
+        for kind in ALL.iter() {
+            assert_eq!(monsters.get(kind).unwrap().kind, *kind);
+        }
+        assert!(monsters.get(&MonsterType::Troll).unwrap().is_melee(&monsters.get(&MonsterType::Troll).unwrap().abilities[0]));
+    }
+
+    #[test]
+    fn only_bosses_change_phase() {
+        let mut dragon = Monster::new("Smaug", &MonsterType::Dragon, 3);
+        dragon.hp = dragon.max_hp / 4;
+        assert_eq!(dragon.next_phase(), None);
+
+        let mut dragon = Monster::boss("Smaug", &MonsterType::Dragon, 3);
+        dragon.hp = dragon.max_hp / 4;
+        assert_eq!(dragon.next_phase().unwrap().below, 60);
+        dragon.phase = Some(2);
+        let abilities = dragon.get_abilities().into_iter().map(|spell| spell.name).collect::<Vec<&str>>();
+        assert_eq!(abilities, vec!["Rend", "Inferno"]);
+    }
+
+    #[test]
+    fn levels_make_a_monster_stronger() {
+        let green = Monster::new("Grub", &MonsterType::Orc, 1);
+        let veteran = Monster::new("Grub", &MonsterType::Orc, 4);
+        assert!(veteran.max_hp > green.max_hp);
+        assert_eq!(veteran.affinity.air, green.affinity.air + 3 * AFFINITY_PER_LEVEL);
+        let style = green.acceptance.get_highest();
+        assert_eq!(veteran.acceptance.get_highest(), style);
+        assert_eq!(veteran.acceptance.val(style), 4);
+        assert_eq!(green.acceptance.val(style), 1);
+    }
+}
@@ -0,0 +1,539 @@
+use std::collections::HashMap;
+use std::io::Result;
+
+use super::boss::{Phase, PhaseShift};
+use super::mon::MonsterType;
+use super::party::Row;
+use super::spell::Spell;
+use super::spell_registry::{invalid, set, Draft, Parsed, SpellRegistry, Tokens};
+use super::status::{Status, Wards, IMMUNE};
+use super::wiz::{Affinity, MindSet};
+use super::{Glyph, Style, BASE_SPEED};
+
+// Every kind of monster that can show up in a battle. The definition file has to
+// describe all the types the game knows by name and can add new ones:
+//
+//   monster Troll
+//     difficulty 4
+//     hp 20
+//     speed 6
+//     mindset Aggressive
+//     ability Bash
+//       glyph Earth 1
+//       style Void 1
+//       priority LowHealth
+//       target Enemy 2
+//       effect 5 Instant Damage
+//       effect 1 Instant Status Stunned 1
+//       melee
+//     ability fireball
+//
+// `difficulty` is how hard the type is to take on at all, which decides how
+// many show up and what they are worth; how hard one monster is depends on its
+// level. `hp` is the hp gained per level, optionally followed by a flat amount
+// every monster of the type starts with.
+// `speed` defaults to 10, `mindset` (Coward, Aggressive, Defensive or Neutral)
+// to Neutral, `acceptance`, the style abilities are cast in, to Void and `row`
+// (Front or Back) to Front.
+// `affinity <glyph> <n>` may be given once per glyph.
+// `immune <status or glyph>` and `resist <status or glyph> <percent>` ward off
+// harmful statuses: a glyph wards off every status brought on with it, and a
+// resisted status lasts that many percent shorter. Each may be warded once.
+// An `ability` followed by spell lines (see spell_registry.rs) is defined right
+// there, one without them is looked up among the monster's own abilities above it,
+// then among the spells wizards can learn.
+// `melee` marks the ability above it as a blow that has to go through the front row,
+// `from <level>` as one only monsters of that level and up have.
+// A boss goes through the phases listed last, in order, as its hp falls:
+//
+//     phase below 30
+//       status Raging 4 10
+//       summon Voidling 3 2
+//       acceptance Eldrich
+//       ability Rend
+//         ...
+//
+// `phase below <percent>` starts one, each below the one before. In it, `status
+// <status> <value> <duration>` puts a status on the boss, `summon <monster> <level>
+// <count>` calls in help and `acceptance <style>` changes the style it casts in.
+// Abilities listed in a phase replace whatever the boss fought with before.
+// Lines starting with # are comments.
+#[derive(Clone, Debug)]
+pub struct MonsterDefinition {
+    pub name: &'static str,
+    pub kind: MonsterType,
+    pub difficulty: u8,
+    pub hp_per_difficulty: u32,
+    pub base_hp: u32,
+    pub speed: u16,
+    pub mindset: MindSet,
+    pub affinity: Affinity,
+    pub acceptance: Style,
+    pub row: Row,
+    pub wards: Wards,
+    pub abilities: Vec<Spell>,
+    pub melee: Vec<&'static str>, // names of the abilities that are melee blows
+    pub learned: Vec<(&'static str, u8)>, // names of the abilities that come with a level, and the level
+    pub phases: Vec<Phase>, // only bosses go through them
+}
+
+impl MonsterDefinition {
+    pub fn hp(&self, difficulty: u32) -> u32 {
+        self.base_hp + difficulty * self.hp_per_difficulty
+    }
+
+    pub fn is_melee(&self, ability: &Spell) -> bool {
+        self.melee.contains(&ability.name)
+    }
+
+    pub fn abilities_at(&self, level: u8) -> impl Iterator<Item = &Spell> {
+        self.abilities.iter().filter(move |ability| self.learned.iter()
+            .all(|(name, from)| *name != ability.name || *from <= level))
+    }
+}
+
+#[derive(Default)]
+pub struct MonsterRegistry {
+    monsters: Vec<MonsterDefinition>,
+    by_type: HashMap<MonsterType, usize>,
+}
+
+impl MonsterRegistry {
+    pub fn new() -> MonsterRegistry {
+        MonsterRegistry {
+            monsters: Vec::new(),
+            by_type: HashMap::new(),
+        }
+    }
+
+    pub fn load(&mut self, path: &str, spells: &SpellRegistry) -> Result<()> {
+        let source = std::fs::read_to_string(path)?;
+        self.parse(&source, path, spells)
+    }
+
+    // Definitions replace the monsters they are named after, if there are any.
+    pub fn parse(&mut self, source: &str, origin: &str, spells: &SpellRegistry) -> Result<()> {
+        let mut defined: HashMap<String, usize> = HashMap::new();
+        let mut draft: Option<MonsterDraft> = None;
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |message: String| invalid(origin, line_number, message);
+            let (keyword, rest) = match line.find(char::is_whitespace) {
+                Some(split) => (&line[..split], line[split..].trim()),
+                None => (line, ""),
+            };
+            if keyword == "monster" {
+                if let Some(draft) = draft.take() {
+                    let monster = draft.finish(origin, spells)?;
+                    self.define(monster);
+                }
+                if rest.is_empty() {
+                    return Err(invalid("monster needs a name".to_string()));
+                }
+                if let Some(first) = defined.insert(rest.to_lowercase(), line_number) {
+                    return Err(invalid(format!("monster `{}` is already defined on line {}", rest, first)));
+                }
+                draft = Some(MonsterDraft::new(rest, line_number));
+                continue;
+            }
+            let monster = match draft.as_mut() {
+                Some(monster) => monster,
+                None => return Err(invalid(format!("`{}` outside of a monster", keyword))),
+            };
+            monster.line(keyword, rest, line_number, origin, spells)?;
+        }
+        if let Some(draft) = draft.take() {
+            let monster = draft.finish(origin, spells)?;
+            self.define(monster);
+        }
+        Ok(())
+    }
+
+    fn define(&mut self, monster: MonsterDefinition) {
+        match self.by_type.get(&monster.kind) {
+            Some(index) => self.monsters[*index] = monster,
+            None => {
+                self.by_type.insert(monster.kind.clone(), self.monsters.len());
+                self.monsters.push(monster);
+            }
+        }
+    }
+
+    pub fn get(&self, kind: &MonsterType) -> Option<&MonsterDefinition> {
+        self.by_type.get(kind).map(|index| &self.monsters[*index])
+    }
+
+    // Names are matched without regard to case, so `direwolf` finds `DireWolf`.
+    pub fn by_name(&self, name: &str) -> Option<&MonsterDefinition> {
+        self.monsters.iter().find(|monster| monster.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, MonsterDefinition> {
+        self.monsters.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.monsters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.monsters.is_empty()
+    }
+}
+
+struct MonsterDraft {
+    name: String,
+    line_number: usize,
+    difficulty: Option<u8>,
+    hp: Option<(u32, u32)>,
+    speed: Option<u16>,
+    mindset: Option<MindSet>,
+    affinity: Affinity,
+    affinities: Vec<Glyph>,
+    acceptance: Option<Style>,
+    row: Option<Row>,
+    wards: Wards,
+    abilities: Vec<Spell>,
+    melee: Vec<&'static str>,
+    learned: Vec<(&'static str, u8)>,
+    ability: Option<Draft>,
+    ability_is_melee: bool,
+    ability_from: Option<u8>,
+    phases: Vec<Phase>,
+    phase: Option<PhaseDraft>,
+}
+
+struct PhaseDraft {
+    below: u32,
+    shifts: Vec<PhaseShift>,
+    abilities: Vec<Spell>,
+}
+
+impl MonsterDraft {
+    fn new(name: &str, line_number: usize) -> MonsterDraft {
+        MonsterDraft {
+            name: name.to_string(),
+            line_number,
+            difficulty: None,
+            hp: None,
+            speed: None,
+            mindset: None,
+            affinity: Affinity::new(),
+            affinities: Vec::new(),
+            acceptance: None,
+            row: None,
+            wards: Wards::new(),
+            abilities: Vec::new(),
+            melee: Vec::new(),
+            learned: Vec::new(),
+            ability: None,
+            ability_is_melee: false,
+            ability_from: None,
+            phases: Vec::new(),
+            phase: None,
+        }
+    }
+
+    // Spell lines belong to the ability above them, anything else ends it.
+    fn line(&mut self, keyword: &str, rest: &str, line_number: usize, origin: &str, spells: &SpellRegistry) -> Result<()> {
+        let invalid = |message: String| invalid(origin, line_number, message);
+        let mut tokens = Tokens::new(rest);
+        let parsed = match keyword {
+            "phase" => {
+                self.close_ability(origin, spells)?;
+                self.close_phase();
+                return self.open_phase(&mut tokens).and_then(|_| tokens.end()).map_err(invalid);
+            }
+            "status" if self.phase.is_some() => tokens.status()
+                .and_then(|status| Ok(PhaseShift::Status(status, tokens.number()?, tokens.number()?)))
+                .map(|shift| self.shift(shift)),
+            "summon" if self.phase.is_some() => tokens.monster_type()
+                .and_then(|kind| Ok(PhaseShift::Summon(kind, tokens.number()?, tokens.number()?)))
+                .map(|shift| self.shift(shift)),
+            "acceptance" if self.phase.is_some() => tokens.style().map(|style| self.shift(PhaseShift::Style(style))),
+            "difficulty" | "hp" | "speed" | "mindset" | "row" | "affinity" | "immune" | "resist" if self.phase.is_some() =>
+                Err(format!("{} has to come before the first phase", keyword)),
+            "from" if self.phase.is_some() => Err("abilities of a phase come with it, not with a level".to_string()),
+            "difficulty" => tokens.number().and_then(|difficulty| set(&mut self.difficulty, "difficulty", difficulty)),
+            "hp" => tokens.number()
+                .and_then(|per_difficulty| Ok((per_difficulty, if tokens.is_empty() { 0 } else { tokens.number()? })))
+                .and_then(|hp| set(&mut self.hp, "hp", hp)),
+            "speed" => tokens.number().and_then(|speed| set(&mut self.speed, "speed", speed)),
+            "mindset" => mindset(&mut tokens).and_then(|mindset| set(&mut self.mindset, "mindset", mindset)),
+            "acceptance" => tokens.style().and_then(|style| set(&mut self.acceptance, "acceptance", style)),
+            "row" => row(&mut tokens).and_then(|row| set(&mut self.row, "row", row)),
+            "affinity" => tokens.glyph().and_then(|glyph| Ok((glyph, tokens.number()?)))
+                .and_then(|(glyph, value)| self.add_affinity(glyph, value)),
+            "immune" => tokens.next("a status or a glyph").and_then(|warded| self.add_ward(warded, IMMUNE)),
+            "resist" => tokens.next("a status or a glyph").and_then(|warded| Ok((warded, tokens.number()?)))
+                .and_then(|(warded, percent)| self.add_ward(warded, percent)),
+            "ability" if rest.is_empty() => Err("ability needs a name".to_string()),
+            "melee" => {
+                return match self.ability.as_ref() {
+                    Some(_) if self.ability_is_melee => Err("melee is set twice".to_string()),
+                    Some(_) => {
+                        self.ability_is_melee = true;
+                        tokens.end()
+                    }
+                    None => Err("melee outside of an ability".to_string()),
+                }
+                .map_err(invalid);
+            }
+            "from" => {
+                return match self.ability.as_ref() {
+                    Some(_) => tokens.number().and_then(|level| set(&mut self.ability_from, "from", level)),
+                    None => Err("from outside of an ability".to_string()),
+                }
+                .and_then(|_| tokens.end())
+                .map_err(invalid);
+            }
+            "ability" => {
+                self.close_ability(origin, spells)?;
+                self.ability = Some(Draft::new(rest, line_number));
+                return Ok(());
+            }
+            _ => {
+                return match self.ability.as_mut() {
+                    Some(ability) => ability.line(keyword, rest),
+                    None => Err(format!("unknown keyword `{}`", keyword)),
+                }
+                .map_err(invalid);
+            }
+        };
+        self.close_ability(origin, spells)?;
+        parsed.and_then(|_| tokens.end()).map_err(invalid)
+    }
+
+    fn add_affinity(&mut self, glyph: Glyph, value: u32) -> Parsed<()> {
+        if self.affinities.contains(&glyph) {
+            return Err(format!("affinity {:?} is set twice", glyph));
+        }
+        match glyph {
+            Glyph::Fire => self.affinity.fire = value,
+            Glyph::Water => self.affinity.water = value,
+            Glyph::Earth => self.affinity.earth = value,
+            Glyph::Air => self.affinity.air = value,
+            Glyph::Void => self.affinity.void = value,
+        }
+        self.affinities.push(glyph);
+        Ok(())
+    }
+
+    // Glyphs are told apart from statuses by name.
+    fn add_ward(&mut self, warded: &str, percent: u8) -> Parsed<()> {
+        if percent == 0 || percent > IMMUNE {
+            return Err(format!("`{}` is not a percentage to resist by", percent));
+        }
+        if let Ok(glyph) = Tokens::new(warded).glyph() {
+            if self.wards.glyphs.iter().any(|(known, _)| *known == glyph) {
+                return Err(format!("{:?} is warded twice", glyph));
+            }
+            self.wards.ward_glyph(&glyph, percent);
+            return Ok(());
+        }
+        let status = match Status::from_name(warded) {
+            Some(status) if status.is_harmful() => status,
+            Some(_) => return Err(format!("{} is not harmful, there is nothing to ward off", warded)),
+            None => return Err(format!("unknown status or glyph `{}`", warded)),
+        };
+        if self.wards.statuses.iter().any(|(known, _)| *known == status) {
+            return Err(format!("{:?} is warded twice", status));
+        }
+        self.wards.ward_status(&status, percent);
+        Ok(())
+    }
+
+    fn close_ability(&mut self, origin: &str, spells: &SpellRegistry) -> Result<()> {
+        let ability = match self.ability.take() {
+            Some(ability) => ability,
+            None => return Ok(()),
+        };
+        let own = self.abilities.iter().find(|spell| spell.name.eq_ignore_ascii_case(&ability.name));
+        let spell = if ability.is_empty() {
+            own.or_else(|| spells.get(&ability.name)).cloned().ok_or_else(|| invalid(
+                origin,
+                ability.line_number,
+                format!("unknown spell `{}`", ability.name),
+            ))?
+        } else {
+            ability.finish(origin)?.0
+        };
+        if self.ability_is_melee {
+            self.melee.push(spell.name);
+            self.ability_is_melee = false;
+        }
+        if let Some(level) = self.ability_from.take() {
+            self.learned.push((spell.name, level));
+        }
+        match self.phase.as_mut() {
+            Some(phase) => phase.abilities.push(spell),
+            None => self.abilities.push(spell),
+        }
+        Ok(())
+    }
+
+    fn open_phase(&mut self, tokens: &mut Tokens) -> Parsed<()> {
+        if !tokens.accept("below") {
+            return Err("expected `below <percent>`".to_string());
+        }
+        let below: u32 = tokens.number()?;
+        let above = self.phases.last().map_or(100, |phase| phase.below);
+        if below == 0 || below >= above {
+            return Err(format!("a phase below {}% has to come under {}%", below, above));
+        }
+        self.phase = Some(PhaseDraft { below, shifts: Vec::new(), abilities: Vec::new() });
+        Ok(())
+    }
+
+    fn shift(&mut self, shift: PhaseShift) {
+        if let Some(phase) = self.phase.as_mut() {
+            phase.shifts.push(shift);
+        }
+    }
+
+    fn close_phase(&mut self) {
+        if let Some(PhaseDraft { below, mut shifts, abilities }) = self.phase.take() {
+            if !abilities.is_empty() {
+                shifts.push(PhaseShift::Abilities(abilities));
+            }
+            self.phases.push(Phase { below, shifts });
+        }
+    }
+
+    // Problems with the monster as a whole are reported against its `monster` line.
+    fn finish(mut self, origin: &str, spells: &SpellRegistry) -> Result<MonsterDefinition> {
+        self.close_ability(origin, spells)?;
+        self.close_phase();
+        let MonsterDraft { name, line_number, difficulty, hp, speed, mindset, affinity, acceptance, row, wards, abilities, melee, learned, phases, .. } = self;
+        let missing = |field: &str| invalid(origin, line_number, format!("monster `{}` has no {}", name, field));
+        let difficulty = difficulty.ok_or_else(|| missing("difficulty"))?;
+        let (hp_per_difficulty, base_hp) = hp.ok_or_else(|| missing("hp"))?;
+        let (name, kind) = match MonsterType::built_in(&name) {
+            Some(kind) => (&*Box::leak(format!("{:?}", kind).into_boxed_str()), kind),
+            None => {
+                let name: &'static str = Box::leak(name.into_boxed_str());
+                (name, MonsterType::Custom(name))
+            }
+        };
+        Ok(MonsterDefinition {
+            name,
+            kind,
+            difficulty,
+            hp_per_difficulty,
+            base_hp,
+            speed: speed.unwrap_or(BASE_SPEED),
+            mindset: mindset.unwrap_or(MindSet::Neutral),
+            affinity,
+            acceptance: acceptance.unwrap_or(Style::Void),
+            row: row.unwrap_or(Row::Front),
+            wards,
+            abilities,
+            melee,
+            learned,
+            phases,
+        })
+    }
+}
+
+fn mindset(tokens: &mut Tokens) -> Parsed<MindSet> {
+    match tokens.next("a mindset")? {
+        "Coward" => Ok(MindSet::Coward),
+        "Aggressive" => Ok(MindSet::Aggressive),
+        "Defensive" => Ok(MindSet::Defensive),
+        "Neutral" => Ok(MindSet::Neutral),
+        token => Err(format!("unknown mindset `{}`", token)),
+    }
+}
+
+fn row(tokens: &mut Tokens) -> Parsed<Row> {
+    match tokens.next("a row")? {
+        "Front" => Ok(Row::Front),
+        "Back" => Ok(Row::Back),
+        token => Err(format!("unknown row `{}`", token)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_broken_definition_says_where() {
+        let source = "\
+monster Troll
+  difficulty 4
+  hp 20
+  mindset Grumpy
+";
+        let err = MonsterRegistry::new().parse(source, "monsters.txt", &SpellRegistry::from_spells(Vec::new())).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "monsters.txt:4: unknown mindset `Grumpy`");
+    }
+
+    #[test]
+    fn names_the_game_knows_keep_their_type() {
+        let source = "\
+monster direwolf
+  difficulty 3
+  hp 20
+monster Mimic
+  difficulty 2
+  hp 15 5
+";
+        let mut monsters = MonsterRegistry::new();
+        monsters.parse(source, "monsters.txt", &SpellRegistry::from_spells(Vec::new())).unwrap();
+        assert_eq!(monsters.get(&MonsterType::DireWolf).unwrap().name, "DireWolf");
+        let mimic = monsters.by_name("mimic").unwrap();
+        assert_eq!(mimic.kind, MonsterType::Custom("Mimic"));
+        assert_eq!(mimic.hp(2), 35);
+    }
+    #[test]
+    fn a_custom_boss_goes_through_its_phases() {
+        let source = "\
+monster Mimic
+  difficulty 2
+  hp 15
+  ability Chomp
+    glyph Earth 1
+    style Void 1
+    priority Squishy
+    target Enemy 1
+    effect 4 Instant Damage
+  phase below 50
+    status Hardened 2 3
+    summon Rat 1 2
+  phase below 25
+    acceptance Eldrich
+    ability Chomp
+";
+        let mut monsters = MonsterRegistry::new();
+        monsters.parse(source, "monsters.txt", &SpellRegistry::from_spells(Vec::new())).unwrap();
+        let mimic = monsters.by_name("Mimic").unwrap();
+        assert_eq!(mimic.abilities.len(), 1);
+        assert_eq!(mimic.phases, vec![
+            Phase {
+                below: 50,
+                shifts: vec![PhaseShift::Status(Status::Hardened, 2, 3), PhaseShift::Summon(MonsterType::Rat, 1, 2)],
+            },
+            Phase {
+                below: 25,
+                shifts: vec![PhaseShift::Style(Style::Eldrich), PhaseShift::Abilities(mimic.abilities.clone())],
+            },
+        ]);
+    }
+
+    #[test]
+    fn phases_come_last_and_in_falling_order() {
+        let head = "monster Mimic\n  difficulty 2\n  hp 15\n  phase below 50\n";
+        let parse = |tail: &str| MonsterRegistry::new()
+            .parse(&format!("{}{}", head, tail), "monsters.txt", &SpellRegistry::from_spells(Vec::new()))
+            .unwrap_err()
+            .to_string();
+        assert_eq!(parse("  phase below 60\n"), "monsters.txt:5: a phase below 60% has to come under 50%");
+        assert_eq!(parse("  speed 4\n"), "monsters.txt:5: speed has to come before the first phase");
+    }
+}
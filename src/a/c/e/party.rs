@@ -1,11 +1,36 @@
 use generational_arena::Index;
 use uuid::Uuid;
 
+// Where a combatant stands. Melee blows land on the front row while anyone is left in it.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub enum Row {
+    Front,
+    Back,
+}
+
+impl Row {
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Row::Front => 0,
+            Row::Back => 1,
+        }
+    }
+
+    pub fn from_u8(byte: u8) -> std::io::Result<Row> {
+        match byte {
+            0 => Ok(Row::Front),
+            1 => Ok(Row::Back),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid row")),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Party {
     pub uuid: Uuid,
     pub id: Option<Index>,
     pub members: Vec<Index>,
+    pub back_row: Vec<Index>, // members not listed here stand in the front row
 }
 
 impl Party {
@@ -13,9 +38,23 @@ impl Party {
         Party {
             id: None,
             uuid: Uuid::new_v4(),
-            members
+            members,
+            back_row: Vec::new(),
+        }
+    }
+
+    pub fn row(&self, member: &Index) -> Row {
+        if self.back_row.contains(member) {
+            Row::Back
+        } else {
+            Row::Front
         }
     }
 
-    
-}
\ No newline at end of file
+    pub fn set_row(&mut self, member: Index, row: Row) {
+        self.back_row.retain(|back| *back != member);
+        if row == Row::Back {
+            self.back_row.push(member);
+        }
+    }
+}
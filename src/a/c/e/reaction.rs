@@ -0,0 +1,65 @@
+use super::Glyph;
+use super::status::{Status, StatusSet};
+
+const BARRIERS: [Status; 5] = [
+    Status::Barrier(Glyph::Fire),
+    Status::Barrier(Glyph::Water),
+    Status::Barrier(Glyph::Earth),
+    Status::Barrier(Glyph::Air),
+    Status::Barrier(Glyph::Void),
+];
+
+// What happens when a harmful effect of one glyph lands on a target carrying a
+// status that doesn't agree with it. The status is used up by the reaction.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Reaction {
+    Steam,     // Water on Burning: the fire goes out in a burst of scalding steam
+    Evaporate, // Fire on Submerged: the water boils away
+    Stagger,   // Air on Submerged: the target is knocked off its feet and stunned
+    Unravel,   // Void on any Barrier: every barrier is eaten away
+    Shatter,   // Earth on Frozen: the ice breaks, and so does whatever is inside it
+}
+
+impl Reaction {
+    pub fn between(glyph: &Glyph, statuses: &StatusSet) -> Option<Reaction> {
+        match glyph {
+            Glyph::Water if statuses.has(&Status::Burning) => Some(Reaction::Steam),
+            Glyph::Fire if statuses.has(&Status::Submerged) => Some(Reaction::Evaporate),
+            Glyph::Air if statuses.has(&Status::Submerged) => Some(Reaction::Stagger),
+            Glyph::Earth if statuses.has(&Status::Frozen) => Some(Reaction::Shatter),
+            Glyph::Void if BARRIERS.iter().any(|barrier| statuses.has(barrier)) => Some(Reaction::Unravel),
+            _ => None,
+        }
+    }
+
+    // The statuses the reaction takes off the target.
+    pub fn consumes(&self, statuses: &StatusSet) -> Vec<Status> {
+        match self {
+            Reaction::Steam => vec![Status::Burning],
+            Reaction::Evaporate | Reaction::Stagger => vec![Status::Submerged],
+            Reaction::Unravel => BARRIERS.iter().filter(|barrier| statuses.has(barrier)).cloned().collect(),
+            Reaction::Shatter => vec![Status::Frozen],
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Reaction::Steam => 0,
+            Reaction::Evaporate => 1,
+            Reaction::Stagger => 2,
+            Reaction::Unravel => 3,
+            Reaction::Shatter => 4,
+        }
+    }
+
+    pub fn from_u8(byte: u8) -> std::io::Result<Reaction> {
+        match byte {
+            0 => Ok(Reaction::Steam),
+            1 => Ok(Reaction::Evaporate),
+            2 => Ok(Reaction::Stagger),
+            3 => Ok(Reaction::Unravel),
+            4 => Ok(Reaction::Shatter),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid reaction")),
+        }
+    }
+}
@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind, Result};
+
+use super::spell::{
+    Ability, Effect, EffectApplication, EffectDuration, EffectStep, PriorityType, PriorityTypes,
+    Spell, TargetType,
+};
+use super::mon::{self, MonsterType};
+use super::status::Status;
+use super::{Glyph, Style};
+
+// Every spell a wizard can learn, as read from a definition file:
+//
+//   spell fireball
+//     glyph Fire 1
+//     style Arcane 1
+//     priority Squishy
+//     target Enemy 1
+//     effect 5 Instant Damage
+//     effect 3 Instant Status Burning 4
+//
+// `priority` takes one priority or two joined by `or`/`and`, where a priority is
+// Squishy, Tanky, LowHealth, HighHealth, Has <status> or No <status>.
+// `target` is MeAlone, Ally <n>, Enemy <n>, AllAllies, AllEnemies, Everyone,
+// RandomEnemy <n> or Splash (the first enemy plus half as much on its neighbours).
+// `effect` is <value> <duration> <application>, durations being Instant,
+// OverTime <n>, Growth <n> <growth> or AfterXTime <n>, and applications Damage,
+// Heal, Status <status> <duration>, RemoveStatus <status> or Summon <monster> <level>,
+// which calls in as many of a built-in monster type as the effect's value and is
+// left to monster abilities, as wizards have no one to call on.
+// Effects run one after the other, as many as the spell has. An effect can be
+// followed by `on <target>` to hit other targets than the spell does, and by
+// `if <priority>` to only land on targets meeting it, optionally with
+// `else <effect>` for the targets that don't:
+//
+//     effect 3 Instant Damage if Has Burning else 1 Instant Status Burning 3
+// Statuses are written by name, barriers with their glyph (Barrier Earth).
+// Lines starting with # are comments.
+pub struct SpellRegistry {
+    spells: Vec<Spell>,
+    by_name: HashMap<String, usize>,
+}
+
+impl SpellRegistry {
+    pub fn load(path: &str) -> Result<SpellRegistry> {
+        let source = std::fs::read_to_string(path)?;
+        SpellRegistry::parse(&source, path)
+    }
+
+    pub fn from_spells(spells: Vec<Spell>) -> SpellRegistry {
+        let by_name = spells.iter().enumerate()
+            .map(|(index, spell)| (spell.name.to_lowercase(), index))
+            .collect();
+        SpellRegistry { spells, by_name }
+    }
+
+    pub fn parse(source: &str, origin: &str) -> Result<SpellRegistry> {
+        let mut registry = SpellRegistry {
+            spells: Vec::new(),
+            by_name: HashMap::new(),
+        };
+        let mut draft: Option<Draft> = None;
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |message: String| invalid(origin, line_number, message);
+            let (keyword, rest) = match line.find(char::is_whitespace) {
+                Some(split) => (&line[..split], line[split..].trim()),
+                None => (line, ""),
+            };
+            if keyword == "spell" {
+                if let Some(draft) = draft.take() {
+                    registry.push(draft.finish(origin)?, origin)?;
+                }
+                if rest.is_empty() {
+                    return Err(invalid("spell needs a name".to_string()));
+                }
+                draft = Some(Draft::new(rest, line_number));
+                continue;
+            }
+            let spell = match draft.as_mut() {
+                Some(spell) => spell,
+                None => return Err(invalid(format!("`{}` outside of a spell", keyword))),
+            };
+            spell.line(keyword, rest).map_err(invalid)?;
+        }
+        if let Some(draft) = draft.take() {
+            registry.push(draft.finish(origin)?, origin)?;
+        }
+        Ok(registry)
+    }
+
+    fn push(&mut self, (spell, line_number): (Spell, usize), origin: &str) -> Result<()> {
+        // Only monsters have anyone to call on; a wizard's summon would go unanswered.
+        if spell.effects().iter().any(|effect| effect.summons()) {
+            return Err(invalid(origin, line_number, format!("spell `{}` summons, which only monster abilities can", spell.name)));
+        }
+        let key = spell.name.to_lowercase();
+        if self.by_name.contains_key(&key) {
+            return Err(invalid(origin, line_number, format!("spell `{}` is defined twice", spell.name)));
+        }
+        self.by_name.insert(key, self.spells.len());
+        self.spells.push(spell);
+        Ok(())
+    }
+
+    // Names are matched without regard to case, so `Fireball` finds `fireball`.
+    pub fn get(&self, name: &str) -> Option<&Spell> {
+        self.by_name.get(&name.to_lowercase()).map(|index| &self.spells[*index])
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Spell> {
+        self.spells.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.spells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spells.is_empty()
+    }
+
+    pub fn by_glyph(&self, glyph: &Glyph) -> Vec<&Spell> {
+        self.spells.iter().filter(|spell| &spell.glyph.0 == glyph).collect()
+    }
+
+    pub fn by_style(&self, style: &Style) -> Vec<&Spell> {
+        self.spells.iter().filter(|spell| &spell.style.0 == style).collect()
+    }
+
+    pub fn by_glyph_and_style(&self, glyph: &Glyph, style: &Style) -> Vec<&Spell> {
+        self.spells.iter()
+            .filter(|spell| &spell.glyph.0 == glyph && &spell.style.0 == style)
+            .collect()
+    }
+}
+
+pub(super) fn invalid(origin: &str, line_number: usize, message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("{}:{}: {}", origin, line_number, message))
+}
+
+pub(super) struct Draft {
+    pub(super) name: String,
+    pub(super) line_number: usize,
+    glyph: Option<(Glyph, u16)>,
+    style: Option<(Style, u16)>,
+    priority: Option<PriorityTypes>,
+    target: Option<TargetType>,
+    effects: Vec<EffectStep>,
+}
+
+impl Draft {
+    pub(super) fn new(name: &str, line_number: usize) -> Draft {
+        Draft {
+            name: name.to_string(),
+            line_number,
+            glyph: None,
+            style: None,
+            priority: None,
+            target: None,
+            effects: Vec::new(),
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.glyph.is_none() && self.style.is_none() && self.priority.is_none()
+            && self.target.is_none() && self.effects.is_empty()
+    }
+
+    pub(super) fn line(&mut self, keyword: &str, rest: &str) -> Parsed<()> {
+        let mut tokens = Tokens::new(rest);
+        match keyword {
+            "glyph" => tokens.glyph().and_then(|glyph| Ok((glyph, tokens.number()?)))
+                .and_then(|glyph| set(&mut self.glyph, "glyph", glyph)),
+            "style" => tokens.style().and_then(|style| Ok((style, tokens.number()?)))
+                .and_then(|style| set(&mut self.style, "style", style)),
+            "priority" => tokens.priorities().and_then(|priority| set(&mut self.priority, "priority", priority)),
+            "target" => tokens.target().and_then(|target| set(&mut self.target, "target", target)),
+            "effect" => tokens.step().map(|step| self.effects.push(step)),
+            _ => Err(format!("unknown keyword `{}`", keyword)),
+        }
+        .and_then(|_| tokens.end())
+    }
+
+    // Problems with the spell as a whole are reported against its `spell` line.
+    pub(super) fn finish(self, origin: &str) -> Result<(Spell, usize)> {
+        let Draft { name, line_number, glyph, style, priority, target, effects } = self;
+        let missing = |field: &str| invalid(origin, line_number, format!("spell `{}` has no {}", name, field));
+        let glyph = glyph.ok_or_else(|| missing("glyph"))?;
+        let style = style.ok_or_else(|| missing("style"))?;
+        let priority = priority.ok_or_else(|| missing("priority"))?;
+        let target = target.ok_or_else(|| missing("target"))?;
+        if effects.is_empty() {
+            return Err(missing("effect"));
+        }
+        // Spells are shared freely by value, so their names live for the whole run.
+        let name: &'static str = Box::leak(name.into_boxed_str());
+        Ok((Spell::new(name, glyph, style, Ability::owned(priority, target, effects)), line_number))
+    }
+}
+
+pub(super) fn set<T>(field: &mut Option<T>, name: &str, value: T) -> Parsed<()> {
+    if field.is_some() {
+        return Err(format!("{} is set twice", name));
+    }
+    *field = Some(value);
+    Ok(())
+}
+
+pub(super) struct Tokens<'a> {
+    tokens: std::str::SplitWhitespace<'a>,
+}
+
+pub(super) type Parsed<T> = std::result::Result<T, String>;
+
+impl<'a> Tokens<'a> {
+    pub(super) fn new(line: &'a str) -> Tokens<'a> {
+        Tokens { tokens: line.split_whitespace() }
+    }
+
+    pub(super) fn next(&mut self, expected: &str) -> Parsed<&'a str> {
+        self.tokens.next().ok_or_else(|| format!("expected {}", expected))
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.tokens.clone().next().is_none()
+    }
+
+    pub(super) fn end(&mut self) -> Parsed<()> {
+        match self.tokens.next() {
+            Some(token) => Err(format!("unexpected `{}`", token)),
+            None => Ok(()),
+        }
+    }
+
+    pub(super) fn number<T>(&mut self) -> Parsed<T> where T: std::str::FromStr {
+        let token = self.next("a number")?;
+        token.parse::<T>().map_err(|_| format!("`{}` is not a valid number", token))
+    }
+
+    pub(super) fn glyph(&mut self) -> Parsed<Glyph> {
+        match self.next("a glyph")? {
+            "Fire" => Ok(Glyph::Fire),
+            "Water" => Ok(Glyph::Water),
+            "Earth" => Ok(Glyph::Earth),
+            "Air" => Ok(Glyph::Air),
+            "Void" => Ok(Glyph::Void),
+            token => Err(format!("unknown glyph `{}`", token)),
+        }
+    }
+
+    pub(super) fn style(&mut self) -> Parsed<Style> {
+        match self.next("a style")? {
+            "Elder" => Ok(Style::Elder),
+            "Arcane" => Ok(Style::Arcane),
+            "Ancient" => Ok(Style::Ancient),
+            "Eldrich" => Ok(Style::Eldrich),
+            "Void" => Ok(Style::Void),
+            token => Err(format!("unknown style `{}`", token)),
+        }
+    }
+
+    pub(super) fn status(&mut self) -> Parsed<Status> {
+        match self.next("a status")? {
+            "Barrier" => Ok(Status::Barrier(self.glyph()?)),
+            token => Status::from_name(token).ok_or_else(|| format!("unknown status `{}`", token)),
+        }
+    }
+
+    fn priority(&mut self) -> Parsed<PriorityType> {
+        match self.next("a priority")? {
+            "Squishy" => Ok(PriorityType::Squishy),
+            "Tanky" => Ok(PriorityType::Tanky),
+            "LowHealth" => Ok(PriorityType::LowHealth),
+            "HighHealth" => Ok(PriorityType::HighHealth),
+            "Has" => Ok(PriorityType::HasStatus(self.status()?)),
+            "No" => Ok(PriorityType::NoStatus(self.status()?)),
+            token => Err(format!("unknown priority `{}`", token)),
+        }
+    }
+
+    fn priorities(&mut self) -> Parsed<PriorityTypes> {
+        let first = self.priority()?;
+        match self.tokens.next() {
+            None => Ok(PriorityTypes::Single(first)),
+            Some("or") => Ok(PriorityTypes::Or(first, self.priority()?)),
+            Some("and") => Ok(PriorityTypes::And(first, self.priority()?)),
+            Some(token) => Err(format!("expected `or` or `and`, found `{}`", token)),
+        }
+    }
+
+    fn target(&mut self) -> Parsed<TargetType> {
+        match self.next("a target")? {
+            "MeAlone" => Ok(TargetType::MeAlone),
+            "Ally" => Ok(TargetType::Ally(self.number()?)),
+            "Enemy" => Ok(TargetType::Enemy(self.number()?)),
+            "AllAllies" => Ok(TargetType::AllAllies),
+            "AllEnemies" => Ok(TargetType::AllEnemies),
+            "Everyone" => Ok(TargetType::Everyone),
+            "RandomEnemy" => Ok(TargetType::RandomEnemy(self.number()?)),
+            "Splash" => Ok(TargetType::Splash),
+            token => Err(format!("unknown target `{}`", token)),
+        }
+    }
+
+    fn duration(&mut self) -> Parsed<EffectDuration> {
+        match self.next("a duration")? {
+            "Instant" => Ok(EffectDuration::Instant),
+            "OverTime" => Ok(EffectDuration::OverTime(self.number()?)),
+            "Growth" => Ok(EffectDuration::Growth(self.number()?, self.number()?)),
+            "AfterXTime" => Ok(EffectDuration::AfterXTime(self.number()?)),
+            token => Err(format!("unknown duration `{}`", token)),
+        }
+    }
+
+    pub(super) fn accept(&mut self, word: &str) -> bool {
+        if self.tokens.clone().next() == Some(word) {
+            self.tokens.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn step(&mut self) -> Parsed<EffectStep> {
+        let mut step = EffectStep::new(self.effect()?);
+        if self.accept("on") {
+            step = step.on(self.target()?);
+        }
+        if self.accept("if") {
+            step = step.when(self.priority()?);
+            if self.accept("else") {
+                step = step.otherwise(self.effect()?);
+            }
+        }
+        Ok(step)
+    }
+
+    // Only built-in types: the monster file is read after the spells and leans on them.
+    pub(super) fn monster_type(&mut self) -> Parsed<MonsterType> {
+        let name = self.next("a monster type")?;
+        mon::ALL.iter()
+            .find(|kind| format!("{:?}", kind) == name)
+            .cloned()
+            .ok_or_else(|| format!("unknown monster type `{}`", name))
+    }
+
+    fn effect(&mut self) -> Parsed<Effect> {
+        Ok(Effect::new(self.number()?, self.duration()?, self.application()?))
+    }
+
+    fn application(&mut self) -> Parsed<EffectApplication> {
+        match self.next("an application")? {
+            "Damage" => Ok(EffectApplication::Damage),
+            "Heal" => Ok(EffectApplication::Heal),
+            "Status" => Ok(EffectApplication::Status(self.status()?, self.number()?)),
+            "RemoveStatus" => Ok(EffectApplication::RemoveStatus(self.status()?)),
+            "Summon" => Ok(EffectApplication::Summon(self.monster_type()?, self.number()?)),
+            token => Err(format!("unknown application `{}`", token)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wizards_cannot_learn_to_summon() {
+        let source = "\
+spell rally
+  glyph Void 1
+  style Void 1
+  priority Squishy
+  target MeAlone
+  effect 2 Instant Summon Voidling 1
+";
+        let err = SpellRegistry::parse(source, "spells.txt").err().unwrap();
+        assert_eq!(err.to_string(), "spells.txt:1: spell `rally` summons, which only monster abilities can");
+    }
+
+    #[test]
+    fn a_ritual_runs_as_long_as_it_is_written() {
+        use std::io::Cursor;
+        use crate::a::c::inp::Inputable;
+        use crate::a::c::out::Outputable;
+
+        let source = "\
+spell ritual
+  glyph Fire 1
+  style Elder 1
+  priority Squishy
+  target Enemy 1
+  effect 1 Instant Status Burning 2
+  effect 2 Instant Damage on AllEnemies
+  effect 3 Instant Damage if Has Burning else 1 Instant Status Burning 3
+  effect 4 Instant Heal on MeAlone
+";
+        let registry = SpellRegistry::parse(source, "spells.txt").unwrap();
+        let ritual = registry.get("ritual").unwrap();
+        assert_eq!(ritual.steps().len(), 4);
+        assert_eq!(ritual.step(2).unwrap().target, Some(TargetType::AllEnemies));
+        let branch = ritual.step(3).unwrap();
+        assert_eq!(branch.condition, Some(PriorityType::HasStatus(Status::Burning)));
+        assert_eq!(branch.otherwise, Some(Effect::new(1, EffectDuration::Instant, EffectApplication::Status(Status::Burning, 3))));
+        assert!(ritual.step(5).is_none());
+
+        let bytes = ritual.as_bytes().unwrap();
+        assert_eq!(&Spell::from_bytes(&mut Cursor::new(&bytes)).unwrap(), ritual);
+    }
+}
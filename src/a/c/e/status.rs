@@ -11,138 +11,269 @@ pub enum Status {
     Hardened,
     Fluid,
     Flying,
+    Poisoned,
+    Frozen,
+    Silenced,
+    Taunting,
+    Invisible,
+    Regenerating,
 }
 
-#[derive(Clone, Debug)]
+// What becomes of a status landing on someone who already has it. One that has run out is
+// replaced outright, whatever the rule.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Stacking {
+    Replace,     // the latest one takes over
+    Strongest,   // whichever has the larger value times duration stays
+    Refresh,     // the larger value stays, for the longer of the two durations
+    Stacks(u16), // values add up to the cap, for the longer of the two durations
+    PerCaster,   // every caster keeps one of their own, which only they replace
+}
+
+// What a status does to its bearer on its own, every tick.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum OnTick {
+    Nothing,
+    Damage(Glyph), // the status's value
+    DamageByDuration(Glyph), // the status's value for every tick it has left to run
+    Heal, // the status's value, for as long as it runs
+}
+
+// Which way a status moves a number it applies to, by its value.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Modifier {
+    None,
+    Add,
+    Sub,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct StatusDefinition {
+    pub status: Status,
+    pub name: &'static str,
+    pub harmful: bool,
+    pub stacking: Stacking,
+    pub on_tick: OnTick,
+    pub dealt: Modifier, // damage the bearer deals
+    pub taken: Modifier, // damage the bearer takes
+    pub against: Option<Glyph>, // damage modifiers only apply to damage of this glyph
+    pub healing: Modifier, // heals the bearer gives or gets
+    pub quickens: bool, // the value adds to initiative and to the chance to dodge
+    pub incapacitates: bool, // no turns: casts fizzle and initiative drops to 0
+    pub interrupts: bool, // breaks off channelled steps
+    pub silences: bool, // only spells cast on oneself can be picked
+    pub hides: bool, // can't be singled out by the other side
+    pub taunts: bool, // singled out before anyone else
+}
+
+impl StatusDefinition {
+    pub const fn new(status: Status, name: &'static str) -> StatusDefinition {
+        StatusDefinition {
+            status,
+            name,
+            harmful: false,
+            stacking: Stacking::Strongest,
+            on_tick: OnTick::Nothing,
+            dealt: Modifier::None,
+            taken: Modifier::None,
+            against: None,
+            healing: Modifier::None,
+            quickens: false,
+            incapacitates: false,
+            interrupts: false,
+            silences: false,
+            hides: false,
+            taunts: false,
+        }
+    }
+
+    pub const fn harmful(mut self) -> StatusDefinition {
+        self.harmful = true;
+        self
+    }
+
+    pub const fn on_tick(mut self, on_tick: OnTick) -> StatusDefinition {
+        self.on_tick = on_tick;
+        self
+    }
+
+    pub const fn dealt(mut self, modifier: Modifier) -> StatusDefinition {
+        self.dealt = modifier;
+        self
+    }
+
+    pub const fn taken(mut self, modifier: Modifier) -> StatusDefinition {
+        self.taken = modifier;
+        self
+    }
+
+    pub const fn against(mut self, glyph: Glyph) -> StatusDefinition {
+        self.against = Some(glyph);
+        self
+    }
+
+    pub const fn healing(mut self, modifier: Modifier) -> StatusDefinition {
+        self.healing = modifier;
+        self
+    }
+
+    pub const fn quickens(mut self) -> StatusDefinition {
+        self.quickens = true;
+        self
+    }
+
+    pub const fn incapacitates(mut self) -> StatusDefinition {
+        self.incapacitates = true;
+        self
+    }
+
+    pub const fn stacking(mut self, stacking: Stacking) -> StatusDefinition {
+        self.stacking = stacking;
+        self
+    }
+
+    pub const fn interrupts(mut self) -> StatusDefinition {
+        self.interrupts = true;
+        self
+    }
+
+    pub const fn silences(mut self) -> StatusDefinition {
+        self.silences = true;
+        self
+    }
+
+    pub const fn hides(mut self) -> StatusDefinition {
+        self.hides = true;
+        self
+    }
+
+    pub const fn taunts(mut self) -> StatusDefinition {
+        self.taunts = true;
+        self
+    }
+}
+
+// Every status there is. A status's place in here is its id in saves and replays, so new ones go at the end.
+pub const STATUSES: [StatusDefinition; 20] = [
+    StatusDefinition::new(Status::Barrier(Glyph::Fire), "Barrier").taken(Modifier::Sub).against(Glyph::Fire),
+    StatusDefinition::new(Status::Barrier(Glyph::Water), "Barrier").taken(Modifier::Sub).against(Glyph::Water),
+    StatusDefinition::new(Status::Barrier(Glyph::Earth), "Barrier").taken(Modifier::Sub).against(Glyph::Earth),
+    StatusDefinition::new(Status::Barrier(Glyph::Air), "Barrier").taken(Modifier::Sub).against(Glyph::Air),
+    StatusDefinition::new(Status::Barrier(Glyph::Void), "Barrier").taken(Modifier::Sub).against(Glyph::Void),
+    StatusDefinition::new(Status::Burning, "Burning").harmful().stacking(Stacking::PerCaster).on_tick(OnTick::Damage(Glyph::Fire)),
+    StatusDefinition::new(Status::Stunned, "Stunned").harmful().stacking(Stacking::Refresh).incapacitates().interrupts(),
+    StatusDefinition::new(Status::Submerged, "Submerged").harmful().on_tick(OnTick::Damage(Glyph::Water)),
+    StatusDefinition::new(Status::Shocked, "Shocked").harmful().interrupts().healing(Modifier::Sub),
+    StatusDefinition::new(Status::Weakened, "Weakened").harmful().dealt(Modifier::Sub),
+    StatusDefinition::new(Status::Raging, "Raging").dealt(Modifier::Add),
+    StatusDefinition::new(Status::Hardened, "Hardened").dealt(Modifier::Sub).taken(Modifier::Sub),
+    StatusDefinition::new(Status::Fluid, "Fluid").dealt(Modifier::Add).quickens(),
+    StatusDefinition::new(Status::Flying, "Flying").dealt(Modifier::Add).quickens(),
+    StatusDefinition::new(Status::Poisoned, "Poisoned").harmful().stacking(Stacking::Stacks(5)).on_tick(OnTick::DamageByDuration(Glyph::Void)),
+    StatusDefinition::new(Status::Frozen, "Frozen").harmful().stacking(Stacking::Refresh).incapacitates().interrupts(),
+    StatusDefinition::new(Status::Silenced, "Silenced").harmful().stacking(Stacking::Refresh).silences(),
+    StatusDefinition::new(Status::Taunting, "Taunting").stacking(Stacking::Replace).taunts(),
+    StatusDefinition::new(Status::Invisible, "Invisible").hides(),
+    StatusDefinition::new(Status::Regenerating, "Regenerating").on_tick(OnTick::Heal),
+];
+
+impl Status {
+    pub fn definition(&self) -> &'static StatusDefinition {
+        &STATUSES[self.as_u8() as usize]
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        STATUSES.iter()
+            .position(|definition| definition.status == *self)
+            .unwrap_or_else(|| panic!("Unregistered status: {:?}", self)) as u8
+    }
+
+    pub fn from_u8(id: u8) -> Self {
+        match STATUSES.get(id as usize) {
+            Some(definition) => definition.status.clone(),
+            None => panic!("Invalid status byte: {}", id),
+        }
+    }
+
+    // Barriers share a name and are told apart by their glyph, so they aren't found by it.
+    pub fn from_name(name: &str) -> Option<Status> {
+        STATUSES.iter()
+            .find(|definition| definition.name == name && definition.against.is_none())
+            .map(|definition| definition.status.clone())
+    }
+
+    pub fn is_harmful(&self) -> bool {
+        self.definition().harmful
+    }
+}
+
+// One application of a status.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct StatusInstance {
+    pub value: u16,
+    pub duration: u16,
+    pub source: Option<usize>, // who put it on, kept for harmful statuses only; those come from the other side
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct StatusSet {
-    pub(in super::super) barrier_fire: (u16, u16),
-    pub(in super::super) barrier_air: (u16, u16),
-    pub(in super::super) barrier_earth: (u16, u16),
-    pub(in super::super) barrier_water: (u16, u16),
-    pub(in super::super) barrier_void: (u16, u16),
-    pub(in super::super) burning: (u16, u16),
-    pub(in super::super) stunned: (u16, u16),
-    pub(in super::super) submerged: (u16, u16),
-    pub(in super::super) shocked: (u16, u16),
-    pub(in super::super) weakened: (u16, u16),
-    pub(in super::super) raging: (u16, u16),
-    pub(in super::super) hardened: (u16, u16),
-    pub(in super::super) fluid: (u16, u16),
-    pub(in super::super) flying: (u16, u16),
+    pub(in super::super) slots: Vec<Vec<StatusInstance>>, // one for every status, in the order of STATUSES; more than one instance only for PerCaster
 }
 
 impl StatusSet {
     pub fn new() -> Self {
         StatusSet {
-            barrier_fire: (0, 0),
-            barrier_air: (0, 0),
-            barrier_earth: (0, 0),
-            barrier_water: (0, 0),
-            barrier_void: (0, 0),
-            burning: (0, 0),
-            stunned: (0, 0),
-            submerged: (0, 0),
-            shocked: (0, 0),
-            weakened: (0, 0),
-            raging: (0, 0),
-            hardened: (0, 0),
-            fluid: (0, 0),
-            flying: (0, 0),
+            slots: vec![Vec::new(); STATUSES.len()],
         }
     }
 
-    pub fn insert(&mut self, status: &Status, value: u16, duration: u16) {
-        match status {
-            Status::Barrier(Glyph::Fire) => {
-                if value * duration > self.barrier_fire.0 * self.barrier_fire.1 {
-                    self.barrier_fire = (value, duration)
-                }
-            },
-            Status::Barrier(Glyph::Air) => {
-                if value * duration > self.barrier_air.0 * self.barrier_air.1 {
-                    self.barrier_air = (value, duration)
-                }
-            },
-            Status::Barrier(Glyph::Earth) => {
-                if value * duration > self.barrier_earth.0 * self.barrier_earth.1 {
-                    self.barrier_earth = (value, duration)
-                }
-            },
-            Status::Barrier(Glyph::Water) => {
-                if value * duration > self.barrier_water.0 * self.barrier_water.1 {
-                    self.barrier_water = (value, duration)
-                }
-            },
-            Status::Barrier(Glyph::Void) => {
-                if value * duration > self.barrier_void.0 * self.barrier_void.1 {
-                    self.barrier_void = (value, duration)
-                }
-            },
-            Status::Burning => {
-                if value * duration > self.burning.0 * self.burning.1 {
-                    self.burning = (value, duration)
-                }
-            },
-            Status::Stunned => {
-                if value * duration > self.stunned.0 * self.stunned.1 {
-                    self.stunned = (value, duration)
-                }
-            },
-            Status::Submerged => {
-                if value * duration > self.submerged.0 * self.submerged.1 {
-                    self.submerged = (value, duration)
-                }
-            },
-            Status::Shocked => {
-                if value * duration > self.shocked.0 * self.shocked.1 {
-                    self.shocked = (value, duration)
-                }
-            },
-            Status::Weakened => {
-                if value * duration > self.weakened.0 * self.weakened.1 {
-                    self.weakened = (value, duration)
-                }
-            },
-            Status::Raging => {
-                if value * duration > self.raging.0 * self.raging.1 {
-                    self.raging = (value, duration)
-                }
-            },
-            Status::Hardened => {
-                if value * duration > self.hardened.0 * self.hardened.1 {
-                    self.hardened = (value, duration)
-                }
-            },
-            Status::Fluid => {
-                if value * duration > self.fluid.0 * self.fluid.1 {
-                    self.fluid = (value, duration)
+    pub fn insert(&mut self, status: &Status, value: u16, duration: u16, source: Option<usize>) {
+        // Something that would run out before it ever ticks is never put on.
+        if duration == 0 {
+            return;
+        }
+        let definition = status.definition();
+        let source = if definition.harmful { source } else { None };
+        let new = StatusInstance { value, duration, source };
+        let instances = &mut self.slots[status.as_u8() as usize];
+        if definition.stacking == Stacking::PerCaster {
+            instances.retain(|old| old.duration != 0 && old.source != source);
+            instances.push(new);
+            return;
+        }
+        let old = match instances.first_mut() {
+            Some(old) if old.duration != 0 => old,
+            _ => {
+                *instances = vec![new];
+                return;
+            }
+        };
+        match definition.stacking {
+            Stacking::PerCaster => unreachable!("every caster's instance is kept apart above"),
+            Stacking::Replace => *old = new,
+            Stacking::Strongest => {
+                if value as u32 * duration as u32 > old.value as u32 * old.duration as u32 {
+                    *old = new
                 }
+            }
+            Stacking::Refresh => *old = StatusInstance {
+                value: old.value.max(value),
+                duration: old.duration.max(duration),
+                source,
             },
-            Status::Flying => {
-                if value * duration > self.flying.0 * self.flying.1 {
-                    self.flying = (value, duration)
-                }
+            Stacking::Stacks(cap) => *old = StatusInstance {
+                value: old.value.saturating_add(value).min(cap),
+                duration: old.duration.max(duration),
+                source,
             },
         }
     }
 
+    // All of a status's running instances together.
     pub fn value(&self, status: &Status) -> u16 {
-        match status {
-            Status::Barrier(Glyph::Fire) => self.barrier_fire.0,
-            Status::Barrier(Glyph::Air) => self.barrier_air.0,
-            Status::Barrier(Glyph::Earth) => self.barrier_earth.0,
-            Status::Barrier(Glyph::Water) => self.barrier_water.0,
-            Status::Barrier(Glyph::Void) => self.barrier_void.0,
-            Status::Burning => self.burning.0,
-            Status::Stunned => self.stunned.0,
-            Status::Submerged => self.submerged.0,
-            Status::Shocked => self.shocked.0,
-            Status::Weakened => self.weakened.0,
-            Status::Raging => self.raging.0,
-            Status::Hardened => self.hardened.0,
-            Status::Fluid => self.fluid.0,
-            Status::Flying => self.flying.0,
-        }
+        self.slots[status.as_u8() as usize].iter()
+            .filter(|instance| instance.duration != 0)
+            .fold(0u16, |value, instance| value.saturating_add(instance.value))
     }
 
     pub fn entry(&self, status: &Status) -> Option<u16> {
@@ -154,229 +285,229 @@ impl StatusSet {
         }
     }
 
+    // The longest any of a status's instances has left.
     pub fn duration(&self, status: &Status) -> u16 {
-        match status {
-            Status::Barrier(Glyph::Fire) => self.barrier_fire.1,
-            Status::Barrier(Glyph::Air) => self.barrier_air.1,
-            Status::Barrier(Glyph::Earth) => self.barrier_earth.1,
-            Status::Barrier(Glyph::Water) => self.barrier_water.1,
-            Status::Barrier(Glyph::Void) => self.barrier_void.1,
-            Status::Burning => self.burning.1,
-            Status::Stunned => self.stunned.1,
-            Status::Submerged => self.submerged.1,
-            Status::Shocked => self.shocked.1,
-            Status::Weakened => self.weakened.1,
-            Status::Raging => self.raging.1,
-            Status::Hardened => self.hardened.1,
-            Status::Fluid => self.fluid.1,
-            Status::Flying => self.flying.1,
-        }
+        self.slots[status.as_u8() as usize].iter()
+            .map(|instance| instance.duration)
+            .max()
+            .unwrap_or(0)
     }
 
     pub fn remove(&mut self, status: &Status) {
-        match status {
-            Status::Barrier(Glyph::Fire) => self.barrier_fire = (0, 0),
-            Status::Barrier(Glyph::Air) => self.barrier_air = (0, 0),
-            Status::Barrier(Glyph::Earth) => self.barrier_earth = (0, 0),
-            Status::Barrier(Glyph::Water) => self.barrier_water = (0, 0),
-            Status::Barrier(Glyph::Void) => self.barrier_void = (0, 0),
-            Status::Burning => self.burning = (0, 0),
-            Status::Stunned => self.stunned = (0, 0),
-            Status::Submerged => self.submerged = (0, 0),
-            Status::Shocked => self.shocked = (0, 0),
-            Status::Weakened => self.weakened = (0, 0),
-            Status::Raging => self.raging = (0, 0),
-            Status::Hardened => self.hardened = (0, 0),
-            Status::Fluid => self.fluid = (0, 0),
-            Status::Flying => self.flying = (0, 0),
-        }
+        self.slots[status.as_u8() as usize].clear();
     }
 
     pub fn has(&self, status: &Status) -> bool {
-        match status {
-            Status::Barrier(Glyph::Fire) => self.barrier_fire.1 != 0,
-            Status::Barrier(Glyph::Air) => self.barrier_air.1 != 0,
-            Status::Barrier(Glyph::Earth) => self.barrier_earth.1 != 0,
-            Status::Barrier(Glyph::Water) => self.barrier_water.1 != 0,
-            Status::Barrier(Glyph::Void) => self.barrier_void.1 != 0,
-            Status::Burning => self.burning.1 != 0,
-            Status::Stunned => self.stunned.1 != 0,
-            Status::Submerged => self.submerged.1 != 0,
-            Status::Shocked => self.shocked.1 != 0,
-            Status::Weakened => self.weakened.1 != 0,
-            Status::Raging => self.raging.1 != 0,
-            Status::Hardened => self.hardened.1 != 0,
-            Status::Fluid => self.fluid.1 != 0,
-            Status::Flying => self.flying.1 != 0,
-        }
+        self.duration(status) != 0
     }
 
+    // Instances that run out are dropped there and then.
     pub fn tick(&mut self, status: &Status) -> bool {
-        match status {
-            Status::Barrier(Glyph::Fire) => {
-                if self.barrier_fire.1 == 0 {
-                    return false;
-                }
-                self.barrier_fire.1 -= 1;
-                self.barrier_fire.1 != 0
-            }
-            Status::Barrier(Glyph::Air) => {
-                if self.barrier_air.1 == 0 {
-                    return false;
-                }
-                self.barrier_air.1 -= 1;
-                self.barrier_air.1 != 0
-            }
-            Status::Barrier(Glyph::Earth) => {
-                if self.barrier_earth.1 == 0 {
-                    return false;
-                }
-                self.barrier_earth.1 -= 1;
-                self.barrier_earth.1 != 0
-            }
-            Status::Barrier(Glyph::Water) => {
-                if self.barrier_water.1 == 0 {
-                    return false;
-                }
-                self.barrier_water.1 -= 1;
-                self.barrier_water.1 != 0
-            }
-            Status::Barrier(Glyph::Void) => {
-                if self.barrier_void.1 == 0 {
-                    return false;
-                }
-                self.barrier_void.1 -= 1;
-                self.barrier_void.1 != 0
-            }
-            Status::Burning => {
-                if self.burning.1 == 0 {
-                    return false;
-                }
-                self.burning.1 -= 1;
-                self.burning.1 != 0
-            }
-            Status::Stunned => {
-                if self.stunned.1 == 0 {
-                    return false;
-                }
-                self.stunned.1 -= 1;
-                self.stunned.1 != 0
-            }
-            Status::Submerged => {
-                if self.submerged.1 == 0 {
-                    return false;
-                }
-                self.submerged.1 -= 1;
-                self.submerged.1 != 0
-            }
-            Status::Shocked => {
-                if self.shocked.1 == 0 {
-                    return false;
-                }
-                self.shocked.1 -= 1;
-                self.shocked.1 != 0
-            }
-            Status::Weakened => {
-                if self.weakened.1 == 0 {
-                    return false;
-                }
-                self.weakened.1 -= 1;
-                self.weakened.1 != 0
-            }
-            Status::Raging => {
-                if self.raging.1 == 0 {
-                    return false;
-                }
-                self.raging.1 -= 1;
-                self.raging.1 != 0
-            }
-            Status::Hardened => {
-                if self.hardened.1 == 0 {
-                    return false;
-                }
-                self.hardened.1 -= 1;
-                self.hardened.1 != 0
-            }
-            Status::Fluid => {
-                if self.fluid.1 == 0 {
-                    return false;
-                }
-                self.fluid.1 -= 1;
-                self.fluid.1 != 0
-            }
-            Status::Flying => {
-                if self.flying.1 == 0 {
-                    return false;
-                }
-                self.flying.1 -= 1;
-                self.flying.1 != 0
-            }
+        let instances = &mut self.slots[status.as_u8() as usize];
+        for instance in instances.iter_mut() {
+            instance.duration = instance.duration.saturating_sub(1);
         }
+        instances.retain(|instance| instance.duration != 0);
+        !instances.is_empty()
     }
 
     pub fn tick_all(&mut self) {
-        if self.barrier_fire.1 > 0 {
-            self.barrier_fire.1 -= 1;
-        }
-        if self.barrier_air.1 > 0 {
-            self.barrier_air.1 -= 1;
-        }
-        if self.barrier_earth.1 > 0 {
-            self.barrier_earth.1 -= 1;
-        }
-        if self.barrier_water.1 > 0 {
-            self.barrier_water.1 -= 1;
-        }
-        if self.barrier_void.1 > 0 {
-            self.barrier_void.1 -= 1;
-        }
-        if self.burning.1 > 0 {
-            self.burning.1 -= 1;
-        }
-        if self.stunned.1 > 0 {
-            self.stunned.1 -= 1;
-        }
-        if self.submerged.1 > 0 {
-            self.submerged.1 -= 1;
-        }
-        if self.shocked.1 > 0 {
-            self.shocked.1 -= 1;
-        }
-        if self.weakened.1 > 0 {
-            self.weakened.1 -= 1;
-        }
-        if self.raging.1 > 0 {
-            self.raging.1 -= 1;
+        for instances in self.slots.iter_mut() {
+            for instance in instances.iter_mut() {
+                instance.duration = instance.duration.saturating_sub(1);
+            }
+            instances.retain(|instance| instance.duration != 0);
         }
-        if self.hardened.1 > 0 {
-            self.hardened.1 -= 1;
+    }
+
+    // The definitions and values of the statuses that are on, in registry order.
+    pub fn active(&self) -> impl Iterator<Item = (&'static StatusDefinition, u16)> + '_ {
+        STATUSES.iter()
+            .map(move |definition| (definition, self.value(&definition.status)))
+            .filter(|(_, value)| *value != 0)
+    }
+
+    // Like active, one instance at a time.
+    pub fn instances(&self) -> impl Iterator<Item = (&'static StatusDefinition, &StatusInstance)> + '_ {
+        STATUSES.iter()
+            .zip(self.slots.iter())
+            .flat_map(|(definition, instances)| instances.iter().map(move |instance| (definition, instance)))
+            .filter(|(_, instance)| instance.value != 0 && instance.duration != 0)
+    }
+
+    pub fn dealt(&self, damage: u16, glyph: &Glyph) -> u16 {
+        self.modify(damage, Some(glyph), |definition| &definition.dealt)
+    }
+
+    pub fn taken(&self, damage: u16, glyph: &Glyph) -> u16 {
+        self.modify(damage, Some(glyph), |definition| &definition.taken)
+    }
+
+    pub fn healing(&self, heal: u16) -> u16 {
+        self.modify(heal, None, |definition| &definition.healing)
+    }
+
+    pub fn quickness(&self) -> u16 {
+        self.active()
+            .filter(|(definition, _)| definition.quickens)
+            .fold(0u16, |quickness, (_, value)| quickness.saturating_add(value))
+    }
+
+    // These go by whether the status is still running, not by its value.
+    pub fn is_incapacitated(&self) -> bool {
+        self.running().any(|definition| definition.incapacitates)
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.running().any(|definition| definition.interrupts)
+    }
+
+    pub fn is_silenced(&self) -> bool {
+        self.running().any(|definition| definition.silences)
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.running().any(|definition| definition.hides)
+    }
+
+    pub fn is_taunting(&self) -> bool {
+        self.running().any(|definition| definition.taunts)
+    }
+
+    fn running(&self) -> impl Iterator<Item = &'static StatusDefinition> + '_ {
+        STATUSES.iter()
+            .filter(move |definition| self.has(&definition.status))
+    }
+
+    // Everything that adds goes on first, then everything that takes away, neither past the bounds of a u16.
+    fn modify(&self, amount: u16, glyph: Option<&Glyph>, modifier: fn(&StatusDefinition) -> &Modifier) -> u16 {
+        let applies = |definition: &StatusDefinition| match &definition.against {
+            Some(against) => glyph == Some(against),
+            None => true,
+        };
+        let (added, taken) = self.active()
+            .filter(|(definition, _)| applies(definition))
+            .fold((0u16, 0u16), |(added, taken), (definition, value)| match modifier(definition) {
+                Modifier::Add => (added.saturating_add(value), taken),
+                Modifier::Sub => (added, taken.saturating_add(value)),
+                Modifier::None => (added, taken),
+            });
+        amount.saturating_add(added).saturating_sub(taken)
+    }
+}
+
+// How much of a harmful status someone shrugs off, in percent of how long it would last: against the
+// status itself, and against every status a glyph brings on. The stronger of the two counts, and 100
+// is immunity. Wards only ever get stronger.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Wards {
+    pub statuses: Vec<(Status, u8)>,
+    pub glyphs: Vec<(Glyph, u8)>,
+}
+
+pub const IMMUNE: u8 = 100;
+
+impl Wards {
+    pub fn new() -> Wards {
+        Wards::default()
+    }
+
+    pub fn ward_status(&mut self, status: &Status, percent: u8) {
+        Self::strengthen(&mut self.statuses, status, percent)
+    }
+
+    pub fn ward_glyph(&mut self, glyph: &Glyph, percent: u8) {
+        Self::strengthen(&mut self.glyphs, glyph, percent)
+    }
+
+    pub fn against(&self, status: &Status, glyph: &Glyph) -> u8 {
+        if !status.is_harmful() {
+            return 0;
         }
-        if self.fluid.1 > 0 {
-            self.fluid.1 -= 1;
+        let by_status = self.statuses.iter().find(|(warded, _)| warded == status).map_or(0, |(_, percent)| *percent);
+        let by_glyph = self.glyphs.iter().find(|(warded, _)| warded == glyph).map_or(0, |(_, percent)| *percent);
+        by_status.max(by_glyph).min(IMMUNE)
+    }
+
+    pub fn is_immune(&self, status: &Status, glyph: &Glyph) -> bool {
+        self.against(status, glyph) == IMMUNE
+    }
+
+    // What is left of the duration; a status that is only resisted still lasts at least a tick.
+    pub fn duration(&self, status: &Status, glyph: &Glyph, duration: u16) -> u16 {
+        match self.against(status, glyph) {
+            0 => duration,
+            IMMUNE => 0,
+            ward => ((duration as u32 * (IMMUNE - ward) as u32 / IMMUNE as u32) as u16).max(1),
         }
-        if self.flying.1 > 0 {
-            self.flying.1 -= 1;
+    }
+
+    fn strengthen<T: PartialEq + Clone>(wards: &mut Vec<(T, u8)>, warded: &T, percent: u8) {
+        let percent = percent.min(IMMUNE);
+        match wards.iter_mut().find(|(known, _)| known == warded) {
+            Some(ward) => ward.1 = ward.1.max(percent),
+            None => wards.push((warded.clone(), percent)),
         }
     }
 }
 
-impl PartialEq for StatusSet {
-    fn eq(&self, other: &Self) -> bool {
-        (self.barrier_fire.0 == 0 && other.barrier_fire.0 == 0 || self.barrier_fire.0 > 0 && other.barrier_fire.0 > 0)
-        && (self.barrier_air.0 == 0 && other.barrier_air.0 == 0 || self.barrier_air.0 > 0 && other.barrier_air.0 > 0)
-        && (self.barrier_earth.0 == 0 && other.barrier_earth.0 == 0 || self.barrier_earth.0 > 0 && other.barrier_earth.0 > 0)
-        && (self.barrier_water.0 == 0 && other.barrier_water.0 == 0 || self.barrier_water.0 > 0 && other.barrier_water.0 > 0)
-        && (self.barrier_void.0 == 0 && other.barrier_void.0 == 0 || self.barrier_void.0 > 0 && other.barrier_void.0 > 0)
-        && (self.burning.0 == 0 && other.burning.0 == 0 || self.burning.0 > 0 && other.burning.0 > 0)
-        && (self.stunned.0 == 0 && other.stunned.0 == 0 || self.stunned.0 > 0 && other.stunned.0 > 0)
-        && (self.submerged.0 == 0 && other.submerged.0 == 0 || self.submerged.0 > 0 && other.submerged.0 > 0)
-        && (self.shocked.0 == 0 && other.shocked.0 == 0 || self.shocked.0 > 0 && other.shocked.0 > 0)
-        && (self.weakened.0 == 0 && other.weakened.0 == 0 || self.weakened.0 > 0 && other.weakened.0 > 0)
-        && (self.raging.0 == 0 && other.raging.0 == 0 || self.raging.0 > 0 && other.raging.0 > 0)
-        && (self.hardened.0 == 0 && other.hardened.0 == 0 || self.hardened.0 > 0 && other.hardened.0 > 0)
-        && (self.fluid.0 == 0 && other.fluid.0 == 0 || self.fluid.0 > 0 && other.fluid.0 > 0)
-        && (self.flying.0 == 0 && other.flying.0 == 0 || self.flying.0 > 0 && other.flying.0 > 0)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_statuses_are_gone() {
+        let mut statuses = StatusSet::new();
+        statuses.insert(&Status::Burning, 3, 2, Some(0));
+        statuses.insert(&Status::Hardened, 4, 1, None);
+        statuses.tick_all();
+        assert_eq!(statuses.value(&Status::Hardened), 0);
+        assert_eq!(statuses.taken(10, &Glyph::Fire), 10);
+        assert_eq!(statuses.instances().count(), 1);
+        statuses.tick_all();
+        assert_eq!(statuses.value(&Status::Burning), 0);
+        assert_eq!(statuses.instances().count(), 0);
+        assert_eq!(statuses, StatusSet::new());
     }
-}
 
-impl Eq for StatusSet {}
\ No newline at end of file
+    #[test]
+    fn a_status_without_duration_does_nothing() {
+        let mut statuses = StatusSet::new();
+        statuses.insert(&Status::Raging, 5, 0, None);
+        assert_eq!(statuses.value(&Status::Raging), 0);
+        assert_eq!(statuses.dealt(10, &Glyph::Air), 10);
+        assert_eq!(statuses.instances().count(), 0);
+    }
+
+    #[test]
+    fn each_status_stacks_its_own_way() {
+        let mut statuses = StatusSet::new();
+        statuses.insert(&Status::Taunting, 5, 5, None);
+        statuses.insert(&Status::Taunting, 1, 1, None);
+        assert_eq!((statuses.value(&Status::Taunting), statuses.duration(&Status::Taunting)), (1, 1));
+
+        statuses.insert(&Status::Raging, 2, 3, None);
+        statuses.insert(&Status::Raging, 5, 1, None);
+        assert_eq!((statuses.value(&Status::Raging), statuses.duration(&Status::Raging)), (2, 3));
+
+        statuses.insert(&Status::Stunned, 1, 4, None);
+        statuses.insert(&Status::Stunned, 3, 1, None);
+        assert_eq!((statuses.value(&Status::Stunned), statuses.duration(&Status::Stunned)), (3, 4));
+
+        statuses.insert(&Status::Poisoned, 2, 2, None);
+        statuses.insert(&Status::Poisoned, 2, 3, None);
+        statuses.insert(&Status::Poisoned, 2, 1, None);
+        assert_eq!((statuses.value(&Status::Poisoned), statuses.duration(&Status::Poisoned)), (5, 3));
+
+        statuses.insert(&Status::Burning, 2, 3, Some(0));
+        statuses.insert(&Status::Burning, 3, 2, Some(1));
+        statuses.insert(&Status::Burning, 1, 4, Some(0));
+        assert_eq!(statuses.value(&Status::Burning), 4);
+        let mut burns = statuses.instances()
+            .filter(|(definition, _)| definition.status == Status::Burning)
+            .map(|(_, instance)| (instance.source, instance.value))
+            .collect::<Vec<(Option<usize>, u16)>>();
+        burns.sort();
+        assert_eq!(burns, vec![(Some(0), 1), (Some(1), 3)]);
+    }
+}
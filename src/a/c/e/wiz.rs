@@ -1,10 +1,14 @@
 use super::spell_book::SpellBook;
 use generational_arena::Index;
 
-use super::Glyph;
+use super::{Glyph, BASE_SPEED};
 use super::Style;
 use super::spell::Spell;
-use super::status::StatusSet;
+use super::status::{StatusSet, Wards};
+use crate::a::q::items::Item;
+
+// Mana a wizard gets back at the end of every battle tick.
+pub const MANA_REGEN: u32 = 1;
 
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub enum MindSet {
@@ -14,6 +18,27 @@ pub enum MindSet {
     Neutral,
 }
 
+impl MindSet {
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            MindSet::Coward => 0,
+            MindSet::Aggressive => 1,
+            MindSet::Defensive => 2,
+            MindSet::Neutral => 3,
+        }
+    }
+
+    pub fn from_u8(byte: u8) -> std::io::Result<MindSet> {
+        match byte {
+            0 => Ok(MindSet::Coward),
+            1 => Ok(MindSet::Aggressive),
+            2 => Ok(MindSet::Defensive),
+            3 => Ok(MindSet::Neutral),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid mindset")),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub struct Affinity {
     pub fire: u32,
@@ -39,7 +64,12 @@ pub struct Wizard {
     pub name: String,
     pub hp: u32,
     pub max_hp: u32,
+    pub mana: u32,
+    pub max_mana: u32,
+    pub speed: u16, // BASE_SPEED for everyone new; saved as it stands
     pub status: StatusSet,
+    pub wards: Wards, // the wizard's own, before anything worn
+    pub amulet: Option<Item>, // its wards count only while it hangs here
     pub(in super::super)selected_spellbook: usize,
     pub(in super::super)spellbooks: Vec<SpellBook>,
     pub state: MindSet,
@@ -54,7 +84,12 @@ impl Wizard {
             name,
             hp: 100,
             max_hp: 100,
+            mana: 30,
+            max_mana: 30,
+            speed: BASE_SPEED,
             status: StatusSet::new(),
+            wards: Wards::new(),
+            amulet: None,
             selected_spellbook: 0,
             spellbooks: vec![SpellBook::new()],
             state: MindSet::Neutral,
@@ -67,6 +102,37 @@ impl Wizard {
         self.spellbooks[self.selected_spellbook].spells()
     }
 
+    // What the wizard could cast right now; the whole book is there once mana runs high enough.
+    pub fn affordable_spells(&self) -> Vec<Spell> {
+        self.get_spells().iter().filter(|spell| spell.cost() <= self.mana).cloned().collect()
+    }
+
+    pub fn spend_mana(&mut self, spell: &Spell) {
+        self.mana = self.mana.saturating_sub(spell.cost());
+    }
+
+    pub fn restore_mana(&mut self, mana: u32) {
+        self.mana = (self.mana + mana).min(self.max_mana);
+    }
+
+    // Hangs the amulet around the wizard's neck, handing back whatever hung there before.
+    pub fn wear(&mut self, amulet: Item) -> Option<Item> {
+        self.amulet.replace(amulet)
+    }
+
+    pub fn take_off(&mut self) -> Option<Item> {
+        self.amulet.take()
+    }
+
+    // The wizard's own wards with whatever is worn on top.
+    pub fn worn_wards(&self) -> Wards {
+        let mut wards = self.wards.clone();
+        if let Some(amulet) = &self.amulet {
+            amulet.ward(&mut wards);
+        }
+        wards
+    }
+
     pub fn augment(&self, glyph: &Glyph) -> u16 {
         self.affinity.val(glyph) as u16
     }
@@ -10,18 +10,26 @@ use crate::a::c::e::spell::EffectDuration;
 use crate::a::c::e::spell::Spell;
 use crate::a::c::e::spell::spells;
 use crate::a::c::e::spell_book::SpellBook;
-use crate::a::c::e::status::StatusSet;
+use crate::a::c::e::status::{StatusSet, StatusInstance, Wards};
 use crate::a::c::e::wiz::MindSet;
 use crate::a::q::battle::Battle;
 use crate::a::q::battle::BattleAtom;
 use crate::a::q::battle::BattleEvent;
 use crate::a::q::battle::BattleMut;
+use crate::a::q::battle::Wave;
+use crate::a::q::items::amulets;
+use crate::a::q::observer::Observers;
+use crate::a::q::replay::{Combatant, Replay, REPLAY_MAGIC, REPLAY_VERSION};
+use crate::a::rng::GameRng;
 
+use super::e::mon;
 use super::e::mon::Monster;
 use super::e::mon::MonsterType;
-use super::e::party::Party;
+use super::e::party::{Party, Row};
+use super::e::reaction::Reaction;
 use super::e::spell::Effect;
-use super::e::spell::EffectProgression;
+use super::e::spell::EffectStep;
+use super::e::spell::PriorityType;
 use super::e::spell::PriorityTypes;
 use super::e::spell::TargetType;
 use super::e::status::Status;
@@ -32,7 +40,7 @@ use byteorder::{LittleEndian, ReadBytesExt};
 use uuid::Uuid;
 use std::collections::HashMap;
 use std::io::Read;
-pub(in super) trait Inputable<T> {
+pub(crate) trait Inputable<T> {
     fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<T>;
 }
 
@@ -81,6 +89,18 @@ impl Inputable<usize> for usize {
     }
 }
 
+impl Inputable<u16> for u16 {
+    fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<u16> {
+        buf.read_u16::<LittleEndian>()
+    }
+}
+
+impl Inputable<u32> for u32 {
+    fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<u32> {
+        buf.read_u32::<LittleEndian>()
+    }
+}
+
 impl Inputable<Wizard> for Wizard {
     fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<Wizard> {
         let mut usize_buf = [0u8; std::mem::size_of::<usize>()];
@@ -92,9 +112,19 @@ impl Inputable<Wizard> for Wizard {
         println!("name: {}", name);
         let hp = buf.read_u32::<LittleEndian>()?;
         let max_hp = buf.read_u32::<LittleEndian>()?;
+        let mana = buf.read_u32::<LittleEndian>()?;
+        let max_mana = buf.read_u32::<LittleEndian>()?;
+        let speed = buf.read_u16::<LittleEndian>()?;
+        let state = MindSet::from_u8(buf.read_u8()?)?;
         let acceptance = Acceptance::from_bytes(buf)?;
         let affinity = Affinity::from_bytes(buf)?;
         let status = StatusSet::from_bytes(buf)?;
+        let wards = Wards::from_bytes(buf)?;
+        let amulet = match buf.read_u8()? {
+            0 => None,
+            _ => Some(amulets::by_name(&String::from_bytes(buf)?)
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "Unknown amulet"))?),
+        };
 
         buf.read_exact(&mut usize_buf).expect("Failed to read wizard name size");
         let selected_spellbook = usize::from_le_bytes(usize_buf);
@@ -105,10 +135,15 @@ impl Inputable<Wizard> for Wizard {
             name,
             hp,
             max_hp,
+            mana,
+            max_mana,
+            speed,
             status,
+            wards,
+            amulet,
             selected_spellbook,
             spellbooks,
-            state: MindSet::Neutral,
+            state,
             affinity,
             acceptance,
         })
@@ -121,21 +156,32 @@ impl Inputable<Monster> for Monster {
         let mut name = vec![0; name_len as usize];
         buf.read_exact(&mut name)?;
         let name = String::from_utf8(name).expect("Invalid utf8");
-        let monster_type = MonsterType::from_u8(buf.read_u8()?);
+        let monster_type = MonsterType::from_bytes(buf)?;
+        let level = buf.read_u8()?;
         let hp = buf.read_u32::<LittleEndian>()?;
         let max_hp = buf.read_u32::<LittleEndian>()?;
+        let speed = buf.read_u16::<LittleEndian>()?;
+        let state = MindSet::from_u8(buf.read_u8()?)?;
         let affinity = Affinity::from_bytes(buf)?;
         let acceptance = Acceptance::from_bytes(buf)?;
         let status = StatusSet::from_bytes(buf)?;
+        let phase = match buf.read_u8()? {
+            0 => None,
+            phase => Some(phase - 1),
+        };
         Ok(Self {
             id: None,
             name,
             monster_type,
+            level,
             affinity,
             acceptance,
             hp,
             max_hp,
+            speed,
+            state,
             status,
+            phase,
         })
     }
 }
@@ -146,7 +192,20 @@ impl Inputable<Battle> for Battle {
         let active_enemies = Vec::<usize>::from_bytes(buf)?;
         let cast_wizard_spells = HashMap::<usize, Spell>::from_bytes(buf)?;
         let used_monster_abilities = HashMap::<usize, Spell>::from_bytes(buf)?;
+        let spell_cooldowns = HashMap::<usize, HashMap<String, u16>>::from_bytes(buf)?;
+        let ally_rows = Vec::<Row>::from_bytes(buf)?;
+        let enemy_rows = Vec::<Row>::from_bytes(buf)?;
+        let waves = Vec::<Wave>::from_bytes(buf)?;
+        let threat = HashMap::<usize, HashMap<usize, u32>>::from_bytes(buf)?;
+        let variance = buf.read_u8()? != 0;
+        let tick_limit = buf.read_u16::<LittleEndian>()?;
+        let stalemate_ticks = buf.read_u16::<LittleEndian>()?;
+        let hp_window = Vec::<Vec<u32>>::from_bytes(buf)?;
         let past_ticks = Vec::<Vec<BattleEvent>>::from_bytes(buf)?;
+        let opening_allies = Vec::<Combatant>::from_bytes(buf)?;
+        let opening_enemies = Vec::<Combatant>::from_bytes(buf)?;
+        let seed = buf.read_u64::<LittleEndian>()?;
+        let rng = GameRng::new(buf.read_u64::<LittleEndian>()?);
         
         Ok(Battle {
             id: None,
@@ -156,7 +215,21 @@ impl Inputable<Battle> for Battle {
             enemies: Vec::new(),
             active_enemies,
             used_monster_abilities,
+            spell_cooldowns,
+            ally_rows,
+            enemy_rows,
+            waves,
+            threat,
+            variance,
+            tick_limit,
+            stalemate_ticks,
+            hp_window,
             past_ticks,
+            opening_allies,
+            opening_enemies,
+            seed,
+            rng,
+            observers: Observers::new(),
         })
     }
 }
@@ -169,6 +242,8 @@ impl Inputable<BattleEvent> for BattleEvent {
             1 => BattleEvent::Monster(BattleAtom::from_bytes(buf)?),
             2 => BattleEvent::Victory,
             3 => BattleEvent::Defeat,
+            4 => BattleEvent::Retreat,
+            5 => BattleEvent::Draw,
             _ => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid event type")),
         })
     }
@@ -249,6 +324,12 @@ impl Inputable<BattleAtom> for BattleAtom {
                     11 => Status::Barrier(Glyph::Earth),
                     12 => Status::Barrier(Glyph::Air),
                     13 => Status::Barrier(Glyph::Void),
+                    14 => Status::Poisoned,
+                    15 => Status::Frozen,
+                    16 => Status::Silenced,
+                    17 => Status::Taunting,
+                    18 => Status::Invisible,
+                    19 => Status::Regenerating,
                     _ => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid status")),
                 };
                 let value = buf.read_u16::<LittleEndian>()?;
@@ -275,6 +356,12 @@ impl Inputable<BattleAtom> for BattleAtom {
                     11 => Status::Barrier(Glyph::Earth),
                     12 => Status::Barrier(Glyph::Air),
                     13 => Status::Barrier(Glyph::Void),
+                    14 => Status::Poisoned,
+                    15 => Status::Frozen,
+                    16 => Status::Silenced,
+                    17 => Status::Taunting,
+                    18 => Status::Invisible,
+                    19 => Status::Regenerating,
                     _ => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid status")),
                 };
                 Ok(BattleAtom::Mutation(BattleMut::LoseStatus(statuser, statusee, status)))
@@ -286,6 +373,85 @@ impl Inputable<BattleAtom> for BattleAtom {
                 let killee = usize::from_le_bytes(usize_buf);
                 Ok(BattleAtom::Kill(killer, killee))
             }
+            9 => {
+                buf.read_exact(&mut usize_buf)?;
+                let combatant = usize::from_le_bytes(usize_buf);
+                let initiative = buf.read_u16::<LittleEndian>()?;
+                Ok(BattleAtom::Initiative(combatant, initiative))
+            }
+            10 => {
+                buf.read_exact(&mut usize_buf)?;
+                let combatant = usize::from_le_bytes(usize_buf);
+                let mindset = MindSet::from_u8(buf.read_u8()?)?;
+                Ok(BattleAtom::MindShift(combatant, mindset))
+            }
+            11 => {
+                buf.read_exact(&mut usize_buf)?;
+                let fled = usize::from_le_bytes(usize_buf);
+                Ok(BattleAtom::Flee(fled))
+            }
+            12 => {
+                buf.read_exact(&mut usize_buf)?;
+                let caster = usize::from_le_bytes(usize_buf);
+                buf.read_exact(&mut usize_buf)?;
+                let target = usize::from_le_bytes(usize_buf);
+                let reaction = Reaction::from_u8(buf.read_u8()?)?;
+                Ok(BattleAtom::Reaction(caster, target, reaction))
+            }
+            13 => {
+                buf.read_exact(&mut usize_buf)?;
+                let caster = usize::from_le_bytes(usize_buf);
+                Ok(BattleAtom::OutOfMana(caster))
+            }
+            14 => {
+                buf.read_exact(&mut usize_buf)?;
+                let caster = usize::from_le_bytes(usize_buf);
+                let spell = Spell::from_bytes(buf)?;
+                Ok(BattleAtom::Interrupted(caster, spell))
+            }
+            15 => {
+                buf.read_exact(&mut usize_buf)?;
+                let wizard = usize::from_le_bytes(usize_buf);
+                let row = Row::from_u8(buf.read_u8()?)?;
+                Ok(BattleAtom::ChangeRow(wizard, row))
+            }
+            16 => {
+                buf.read_exact(&mut usize_buf)?;
+                let summoner = usize::from_le_bytes(usize_buf);
+                let kind = MonsterType::from_bytes(buf)?;
+                Ok(BattleAtom::Summon(summoner, kind, buf.read_u8()?))
+            }
+            17 => {
+                let kind = MonsterType::from_bytes(buf)?;
+                Ok(BattleAtom::Reinforce(kind, buf.read_u8()?))
+            }
+            18 => {
+                buf.read_exact(&mut usize_buf)?;
+                let caster = usize::from_le_bytes(usize_buf);
+                buf.read_exact(&mut usize_buf)?;
+                let target = usize::from_le_bytes(usize_buf);
+                Ok(BattleAtom::Crit(caster, target))
+            }
+            19 => {
+                buf.read_exact(&mut usize_buf)?;
+                let caster = usize::from_le_bytes(usize_buf);
+                buf.read_exact(&mut usize_buf)?;
+                let target = usize::from_le_bytes(usize_buf);
+                Ok(BattleAtom::Miss(caster, target))
+            }
+            20 => {
+                buf.read_exact(&mut usize_buf)?;
+                let boss = usize::from_le_bytes(usize_buf);
+                let phase = buf.read_u8()?;
+                Ok(BattleAtom::PhaseChange(boss, phase))
+            }
+            21 => {
+                buf.read_exact(&mut usize_buf)?;
+                let caster = usize::from_le_bytes(usize_buf);
+                buf.read_exact(&mut usize_buf)?;
+                let target = usize::from_le_bytes(usize_buf);
+                Ok(BattleAtom::Immune(caster, target, Status::from_u8(buf.read_u8()?)))
+            }
             _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid atom type")),
         }
     }
@@ -310,6 +476,66 @@ impl Inputable<SpellBook> for SpellBook {
     }
 }
 
+impl Inputable<Combatant> for Combatant {
+    fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<Combatant> {
+        let name = String::from_bytes(buf)?;
+        let hp = buf.read_u32::<LittleEndian>()?;
+        let max_hp = buf.read_u32::<LittleEndian>()?;
+        let status = StatusSet::from_bytes(buf)?;
+        Ok(Combatant {
+            name,
+            hp,
+            max_hp,
+            status,
+        })
+    }
+}
+
+impl Inputable<Replay> for Replay {
+    fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<Replay> {
+        let mut magic = [0u8; 4];
+        buf.read_exact(&mut magic)?;
+        if &magic != REPLAY_MAGIC {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Not a replay file"));
+        }
+        let version = buf.read_u8()?;
+        if version != REPLAY_VERSION {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Unsupported replay version: {}", version)));
+        }
+        let seed = buf.read_u64::<LittleEndian>()?;
+        let allies = Vec::<Combatant>::from_bytes(buf)?;
+        let enemies = Vec::<Combatant>::from_bytes(buf)?;
+        let ticks = Vec::<Vec<BattleEvent>>::from_bytes(buf)?;
+        Ok(Replay {
+            seed,
+            allies,
+            enemies,
+            ticks,
+        })
+    }
+}
+
+impl Inputable<Row> for Row {
+    fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<Row> {
+        Row::from_u8(buf.read_u8()?)
+    }
+}
+
+impl Inputable<Wave> for Wave {
+    fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<Wave> {
+        let tick = buf.read_u16::<LittleEndian>()?;
+        let mut usize_buf = [0u8; std::mem::size_of::<usize>()];
+        buf.read_exact(&mut usize_buf)?;
+        let count = usize::from_le_bytes(usize_buf);
+        let mut monsters = Vec::new();
+        for _ in 0..count {
+            let kind = MonsterType::from_bytes(buf)?;
+            monsters.push((kind, buf.read_u8()?));
+        }
+        Ok(Wave { tick, monsters })
+    }
+}
+
 impl Inputable<Party> for Party {
     fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<Party> {
         
@@ -319,7 +545,8 @@ impl Inputable<Party> for Party {
         Ok(Party {
             id: None,
             uuid,
-            members: Vec::new()
+            members: Vec::new(),
+            back_row: Vec::new(),
         })
     }
 }
@@ -332,12 +559,10 @@ impl Inputable<Spell> for Spell {
         let style_value = buf.read_u16::<LittleEndian>()?;
         println!("{:?} {} {:?} {}", glyph, glyph_value, style, style_value);
 
-        let mut usize_buf = [0u8; std::mem::size_of::<usize>()];
-        buf.read_exact(&mut usize_buf)?;
-        let spell_index = usize::from_le_bytes(usize_buf);
+        let name = String::from_bytes(buf)?;
         let ability = Ability::from_bytes(buf)?;
         Ok(Self {
-            name: spells::NAME_BY_ID[spell_index],
+            name: spells::intern(&name),
             glyph: (glyph, glyph_value),
             style: (style, style_value),
             ability,
@@ -349,32 +574,28 @@ impl Inputable<Ability> for Ability {
     fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<Self> {
         let priority = PriorityTypes::from_3u8(buf.read_u8()?, buf.read_u8()?, buf.read_u8()?)?;
         let target = TargetType::from_2u8(buf.read_u8()?, buf.read_u8()?)?;
-        let effects = EffectProgression::from_bytes(buf)?;
-        Ok(Self {
-            priority,
-            target,
-            effects,
-        })
+        let steps = buf.read_u8()?;
+        let mut effects = Vec::with_capacity(steps as usize);
+        for _ in 0..steps {
+            effects.push(EffectStep::from_bytes(buf)?);
+        }
+        Ok(Self::owned(priority, target, effects))
     }
 }
 
-impl Inputable<EffectProgression> for EffectProgression {
+impl Inputable<EffectStep> for EffectStep {
     fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<Self> {
-        let kind = buf.read_u8()?;
-        match kind {
-            0 => Ok(Self::None),
-            1 => Ok(Self::Single(Effect::from_bytes(buf)?)),
-            2 => Ok(Self::Duo(Effect::from_bytes(buf)?, Effect::from_bytes(buf)?)),
-            3 => Ok(Self::Trio(
-                Effect::from_bytes(buf)?,
-                Effect::from_bytes(buf)?,
-                Effect::from_bytes(buf)?,
-            )),
-            _ => Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Invalid effect progression kind",
-            )),
+        let mut step = EffectStep::new(Effect::from_bytes(buf)?);
+        if buf.read_u8()? == 1 {
+            step = step.on(TargetType::from_2u8(buf.read_u8()?, buf.read_u8()?)?);
+        }
+        if buf.read_u8()? == 1 {
+            step = step.when(PriorityType::from_u8(buf.read_u8()?)?);
         }
+        if buf.read_u8()? == 1 {
+            step = step.otherwise(Effect::from_bytes(buf)?);
+        }
+        Ok(step)
     }
 }
 
@@ -418,6 +639,7 @@ impl Inputable<EffectApplication> for EffectApplication {
                 buf.read_u16::<LittleEndian>()?,
             )),
             3 => Ok(Self::RemoveStatus(Status::from_u8(buf.read_u8()?))),
+            4 => Ok(Self::Summon(MonsterType::from_bytes(buf)?, buf.read_u8()?)),
             _ => Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 "Invalid effect application kind",
@@ -426,45 +648,59 @@ impl Inputable<EffectApplication> for EffectApplication {
     }
 }
 
+impl Inputable<Wards> for Wards {
+    fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<Wards> {
+        let mut wards = Wards::new();
+        for _ in 0..buf.read_u8()? {
+            let status = Status::from_u8(buf.read_u8()?);
+            wards.ward_status(&status, buf.read_u8()?);
+        }
+        for _ in 0..buf.read_u8()? {
+            let glyph = Glyph::from_u8(buf.read_u8()?);
+            wards.ward_glyph(&glyph, buf.read_u8()?);
+        }
+        Ok(wards)
+    }
+}
+
 impl Inputable<StatusSet> for StatusSet {
     fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<StatusSet> {
-        Ok(StatusSet {
-            barrier_fire: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            barrier_air: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            barrier_earth: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            barrier_water: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            barrier_void: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            burning: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            stunned: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            submerged: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            shocked: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            weakened: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            raging: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            hardened: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            fluid: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-            flying: (buf.read_u16::<LittleEndian>()?, buf.read_u16::<LittleEndian>()?),
-        })
+        let mut status = StatusSet::new();
+        let count = buf.read_u8()? as usize;
+        if count > status.slots.len() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{} statuses, only {} are known", count, status.slots.len())));
+        }
+        let mut usize_buf = [0u8; std::mem::size_of::<usize>()];
+        for instances in status.slots.iter_mut().take(count) {
+            for _ in 0..buf.read_u8()? {
+                let value = buf.read_u16::<LittleEndian>()?;
+                let duration = buf.read_u16::<LittleEndian>()?;
+                let source = match buf.read_u8()? {
+                    0 => None,
+                    _ => {
+                        buf.read_exact(&mut usize_buf)?;
+                        Some(usize::from_le_bytes(usize_buf))
+                    }
+                };
+                instances.push(StatusInstance { value, duration, source });
+            }
+        }
+        Ok(status)
     }
 }
 
-impl Status {
-    pub fn from_u8(id: u8) -> Self {
-        match id {
-            0 => Status::Barrier(Glyph::Fire),
-            1 => Status::Barrier(Glyph::Water),
-            2 => Status::Barrier(Glyph::Earth),
-            3 => Status::Barrier(Glyph::Air),
-            4 => Status::Barrier(Glyph::Void),
-            5 => Status::Burning,
-            6 => Status::Stunned,
-            7 => Status::Submerged,
-            8 => Status::Shocked,
-            9 => Status::Weakened,
-            10 => Status::Raging,
-            11 => Status::Hardened,
-            12 => Status::Fluid,
-            13 => Status::Flying,
-            _ => panic!("Invalid status byte: {}", id),
+
+impl Inputable<MonsterType> for MonsterType {
+    fn from_bytes(buf: &mut Cursor<&[u8]>) -> Result<MonsterType> {
+        match buf.read_u8()? {
+            mon::CUSTOM => {
+                let name = String::from_bytes(buf)?;
+                MonsterType::from_name(&name).ok_or_else(|| std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Unknown monster type: {}", name),
+                ))
+            }
+            kind => Ok(MonsterType::from_u8(kind)),
         }
     }
 }
@@ -499,3 +735,56 @@ impl Inputable<Acceptance> for Acceptance {
 
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a::c::out::Outputable;
+
+    #[test]
+    fn a_bad_mindset_is_an_error_not_a_crash() {
+        let wizard = Wizard::new("Bob".to_string());
+        let mut bytes = wizard.as_bytes().unwrap();
+        assert!(Wizard::from_bytes(&mut Cursor::new(&bytes)).is_ok());
+
+        // Name length, name, hp, max hp, mana, max mana and speed come first.
+        let mindset = std::mem::size_of::<usize>() + "Bob".len() + 4 * 4 + 2;
+        bytes[mindset] = 9;
+        let err = Wizard::from_bytes(&mut Cursor::new(&bytes)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn statuses_come_back_exactly_as_they_were_saved() {
+        let mut statuses = StatusSet::new();
+        statuses.insert(&Status::Burning, 2, 3, Some(0));
+        statuses.insert(&Status::Burning, 4, 1, Some(7));
+        statuses.insert(&Status::Hardened, 5, 2, None);
+        let bytes = statuses.as_bytes().unwrap();
+        assert_eq!(bytes.len(), bytes.capacity());
+        assert_eq!(StatusSet::from_bytes(&mut Cursor::new(&bytes)).unwrap(), statuses);
+
+        let mut weaker = statuses.clone();
+        weaker.insert(&Status::Burning, 1, 3, Some(0));
+        assert_ne!(weaker, statuses);
+    }
+
+    #[test]
+    fn a_wizard_keeps_their_speed_through_a_save() {
+        let mut wizard = Wizard::new("Bob".to_string());
+        assert_eq!(wizard.speed, crate::a::c::e::BASE_SPEED);
+        wizard.speed = 14;
+        let bytes = wizard.as_bytes().unwrap();
+        assert_eq!(Wizard::from_bytes(&mut Cursor::new(&bytes)).unwrap().speed, 14);
+    }
+
+    #[test]
+    fn a_worn_amulet_is_saved_with_its_wearer() {
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.wear(amulets::AMULET_OF_AIR);
+        let bytes = wizard.as_bytes().unwrap();
+        let loaded = Wizard::from_bytes(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(loaded.amulet, Some(amulets::AMULET_OF_AIR));
+        assert_eq!(loaded.wards, Wards::new());
+    }
+}
@@ -5,6 +5,7 @@ use std::fs::File;
 use generational_arena::{Arena, Index};
 
 use crate::a::q::battle::Battle;
+use crate::a::rng::GameRng;
 
 pub mod e;
 
@@ -12,30 +13,34 @@ use e::mon::Monster;
 use e::party::Party;
 use e::wiz::Wizard;
 
-mod inp;
-mod out;
+pub(crate) mod inp;
+pub(crate) mod out;
 
 use inp::Inputable;
 
 const ACTIVE_FILENAME: &str = "./assets/active.colosseum";
 const DEAD_FILENAME: &str = "./assets/dead.colosseum";
+const SAVE_MAGIC: &[u8; 4] = b"TKCS";
+// Bump whenever anything a save holds is laid out differently.
+const SAVE_VERSION: u8 = 1;
 
 pub struct Colosseum {
     wizards: Arena<Wizard>,
     battles: Arena<Battle>,
     parties: Arena<Party>,
     monsters: Arena<Monster>,
+    rng: GameRng,
 }
 
 macro_rules! write_arena {
     ($col:ident . $as:ident { $($to:ident),* $(,)? } << $b:ident) => {{
-        $b.write(&$col.$as.len().to_le_bytes())?;
+        $b.write_all(&$col.$as.len().to_le_bytes())?;
         for (_, it) in $col.$as.iter() {
             let line = it.as_bytes();
             match line {
                 Ok(line) => {
-                    $b.write(&line.len().to_le_bytes()).expect(format!("Failed to write {:?} size", stringify!($as)).as_str());
-                    $b.write(&line)?;
+                    $b.write_all(&line.len().to_le_bytes()).expect(format!("Failed to write {:?} size", stringify!($as)).as_str());
+                    $b.write_all(&line)?;
                     $($col.write::<$to, _>(it, &mut $b);)*
                 }
                 Err(e) => {$b.write_fmt(format_args!("\n{:?}: {}\n", stringify!($as), e))?;}
@@ -61,7 +66,7 @@ macro_rules! read_arena {
             $as[id].id = Some(id);
             map.insert(id.into_raw_parts().0, id);
 
-            $($col.read::<$to, _>($as.get(id).unwrap(), &mut $f, &$map);)*
+            $($col.read::<$to, _>($as.get(id).unwrap(), &mut $f, &$map)?;)*
         }
         $col.$as = $as;
         map
@@ -70,14 +75,25 @@ macro_rules! read_arena {
 
 impl Colosseum {
     pub fn new() -> std::io::Result<Colosseum> {
-        let mut col = Colosseum {
+        let mut col = Colosseum::with_rng(GameRng::from_entropy());
+        col.load()?;
+        Ok(col)
+    }
+
+    // Never touches the save files; for simulations and anything else that
+    // should not disturb the player's colosseum.
+    pub fn headless(seed: u64) -> Colosseum {
+        Colosseum::with_rng(GameRng::new(seed))
+    }
+
+    fn with_rng(rng: GameRng) -> Colosseum {
+        Colosseum {
             wizards: Arena::new(),
             battles: Arena::new(),
             parties: Arena::new(),
             monsters: Arena::new(),
-        };
-        col.load()?;
-        Ok(col)
+            rng,
+        }
     }
 
     pub fn load(&mut self) -> std::io::Result<()> {
@@ -86,23 +102,51 @@ impl Colosseum {
         }
         // Compute battles
         let mut f = File::open(ACTIVE_FILENAME).expect(format!("Failed to open {}", ACTIVE_FILENAME).as_str());
+        self.load_from(&mut f)
+    }
 
+    pub fn load_from<R: Read>(&mut self, mut f: R) -> std::io::Result<()> {
+        let mut magic = [0u8; 4];
+        f.read_exact(&mut magic)?;
+        if &magic != SAVE_MAGIC {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Not a colosseum save"));
+        }
+        let mut version = [0u8; 1];
+        f.read_exact(&mut version)?;
+        if version[0] != SAVE_VERSION {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Unsupported save version: {}", version[0])));
+        }
         let battle_map = read_arena!(self.battles: Battle {} >> f);
         let party_map = read_arena!(self.parties: Party  {} >> f);
 
         read_arena!(self.wizards:  Wizard  {battle_map => Battle, party_map => Party} >> f);
         read_arena!(self.monsters: Monster {battle_map => Battle                    } >> f);
-        f.flush()
+
+        let mut u64_buf = [0u8; std::mem::size_of::<u64>()];
+        f.read_exact(&mut u64_buf)?;
+        self.rng = GameRng::new(u64::from_le_bytes(u64_buf));
+        Ok(())
     }
 
     pub fn save(&self) -> std::io::Result<()> {
-        use out::Outputable;
         let mut f = File::create(ACTIVE_FILENAME)?;
+        self.save_to(&mut f)?;
+        f.flush()
+    }
+
+    pub fn save_to<W: Write>(&self, mut f: W) -> std::io::Result<()> {
+        use out::Outputable;
+        f.write_all(SAVE_MAGIC)?;
+        f.write_all(&[SAVE_VERSION])?;
         write_arena!(self.battles  {}              << f);        
         write_arena!(self.parties  {}              << f);        
         write_arena!(self.wizards  {Battle, Party} << f);
         write_arena!(self.monsters {Battle}        << f);
-        f.flush()
+        f.write_all(&self.rng.state().to_le_bytes())
+    }
+
+    pub fn rng_mut(&mut self) -> &mut GameRng {
+        &mut self.rng
     }
 }
 
@@ -206,10 +250,10 @@ trait Container<B> {
 trait Association<A> where A: Idable {
     fn write<B, T>(&self, a: &A, f: &mut T) where B: Idable + Container<A>, Self: ColosseumArena<B>, T: Write {
         let output : Vec<(Index,Option<usize>)> = self.iter().map(|(id, b)| (id, b.position(a.id().unwrap()))).filter(|(_, p)| p.is_some()).collect();
-        f.write(&output.len().to_le_bytes()).expect("Failed to write number of battles");
+        f.write_all(&output.len().to_le_bytes()).expect("Failed to write number of battles");
         for (id, ui) in output {
-            f.write(&id.into_raw_parts().0.to_le_bytes()).expect("Failed to write index id");
-            f.write(&ui.unwrap().to_le_bytes()).expect("Failed to write usize id");
+            f.write_all(&id.into_raw_parts().0.to_le_bytes()).expect("Failed to write index id");
+            f.write_all(&ui.unwrap().to_le_bytes()).expect("Failed to write usize id");
         }
     }
 
@@ -264,4 +308,34 @@ impl Container<Monster> for Battle {
 }
 
 impl Association<Wizard> for Colosseum {}
-impl Association<Monster> for Colosseum {}
\ No newline at end of file
+impl Association<Monster> for Colosseum {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+
+    #[test]
+    fn the_rng_picks_up_where_the_save_left_off() {
+        let mut col = Colosseum::headless(7);
+        col.insert(Wizard::new("Bob".to_string()));
+        col.rng_mut().next_u64();
+        let mut save = Vec::new();
+        col.save_to(&mut save).unwrap();
+
+        let mut loaded = Colosseum::headless(0);
+        loaded.load_from(Cursor::new(&save)).unwrap();
+        assert_eq!(ColosseumArena::<Wizard>::iter(&loaded).count(), 1);
+        for _ in 0..4 {
+            assert_eq!(loaded.rng_mut().next_u64(), col.rng_mut().next_u64());
+        }
+    }
+
+    #[test]
+    fn a_save_from_another_version_is_refused() {
+        let mut save = Vec::new();
+        Colosseum::headless(7).save_to(&mut save).unwrap();
+        save[SAVE_MAGIC.len()] = SAVE_VERSION + 1;
+        let err = Colosseum::headless(0).load_from(Cursor::new(&save)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
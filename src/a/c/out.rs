@@ -1,13 +1,14 @@
 use std::io::Result;
 use std::collections::HashMap;
-use super::{e::{wiz::{Wizard, Affinity, Acceptance,}, status::{Status, StatusSet}, mon::{Monster, MonsterType}, party::Party, Glyph, Style, spell_book::SpellBook, spell::{Spell, PriorityType, PriorityTypes, spells, EffectProgression, Effect, Ability, TargetType, EffectDuration, EffectApplication}}};
-use super::super::q::battle::{Battle, BattleEvent, BattleAtom, BattleMut};
+use super::{e::{wiz::{Wizard, Affinity, Acceptance,}, status::{Status, StatusInstance, StatusSet, Wards}, mon::{Monster, MonsterType}, party::{Party, Row}, Glyph, Style, spell_book::SpellBook, spell::{Spell, PriorityTypes, EffectStep, Effect, Ability, TargetType, EffectDuration, EffectApplication}}};
+use super::super::q::battle::{Battle, BattleEvent, BattleAtom, BattleMut, Wave};
+use super::super::q::replay::{Combatant, Replay, REPLAY_MAGIC, REPLAY_VERSION};
 
 use byteorder::{LittleEndian, WriteBytesExt};
 use generational_arena::{Index, Arena};
 
 
-pub(in super) trait Outputable {
+pub(crate) trait Outputable {
     fn as_bytes(&self) -> Result<Vec<u8>>;
 }
 
@@ -75,6 +76,18 @@ impl Outputable for usize {
     }
 }
 
+impl Outputable for u16 {
+    fn as_bytes(&self) -> Result<Vec<u8>> {
+        Ok(self.to_le_bytes().to_vec())
+    }
+}
+
+impl Outputable for u32 {
+    fn as_bytes(&self) -> Result<Vec<u8>> {
+        Ok(self.to_le_bytes().to_vec())
+    }
+}
+
 impl Outputable for Index {
     fn as_bytes(&self) -> Result<Vec<u8>> {
         let mut output = Vec::with_capacity(std::mem::size_of::<usize>());
@@ -89,15 +102,27 @@ impl Outputable for Wizard {
         let acceptance_as_bytes = self.acceptance.as_bytes()?;
         let affinity_as_bytes = self.affinity.as_bytes()?;
         let status_as_bytes = self.status.as_bytes()?;
+        let wards_as_bytes = self.wards.as_bytes()?;
+        let amulet_as_bytes = match &self.amulet {
+            Some(amulet) => <&str as Outputable>::as_bytes(&amulet.name())?,
+            None => Vec::new(),
+        };
         let spellbooks_as_bytes = self.spellbooks.as_bytes()?;
         let mut output = Vec::with_capacity(
             std::mem::size_of::<usize>() + // name len
             name_as_bytes.len() + // name
             std::mem::size_of::<u32>() + // hp
             std::mem::size_of::<u32>() + // max_hp
+            std::mem::size_of::<u32>() + // mana
+            std::mem::size_of::<u32>() + // max_mana
+            std::mem::size_of::<u16>() + // speed
+            std::mem::size_of::<u8>() + // state
             acceptance_as_bytes.len() + // acceptance
             affinity_as_bytes.len() + // affinity
             status_as_bytes.len() + // status
+            wards_as_bytes.len() + // wards
+            std::mem::size_of::<u8>() + // whether an amulet is worn
+            amulet_as_bytes.len() + // its name
             std::mem::size_of::<usize>() + // selected_spellbook
             std::mem::size_of::<usize>() + // spellbooks.len()
             spellbooks_as_bytes.len() // spellbooks
@@ -105,9 +130,16 @@ impl Outputable for Wizard {
         output.extend(name_as_bytes);
         output.write_u32::<LittleEndian>(self.hp)?;
         output.write_u32::<LittleEndian>(self.max_hp)?;
+        output.write_u32::<LittleEndian>(self.mana)?;
+        output.write_u32::<LittleEndian>(self.max_mana)?;
+        output.write_u16::<LittleEndian>(self.speed)?;
+        output.write_u8(self.state.as_u8())?;
         output.extend(acceptance_as_bytes);
         output.extend(affinity_as_bytes);
         output.extend(status_as_bytes);
+        output.extend(wards_as_bytes);
+        output.write_u8(self.amulet.is_some() as u8)?;
+        output.extend(amulet_as_bytes);
         output.extend_from_slice(&self.selected_spellbook.to_le_bytes());
         output.extend(spellbooks_as_bytes);
         Ok(output)
@@ -125,18 +157,27 @@ impl Outputable for Monster {
                 std::mem::size_of::<usize>()
                 + name_as_bytes.len()
                 + std::mem::size_of::<u8>()
+                + std::mem::size_of::<u8>()
                 + std::mem::size_of::<u32>()
                 + std::mem::size_of::<u32>()
+                + std::mem::size_of::<u16>()
+                + std::mem::size_of::<u8>()
                 + affinity_as_bytes.len()
                 + acceptance_as_bytes.len()
-                + status_as_bytes.len());
+                + status_as_bytes.len()
+                + std::mem::size_of::<u8>());
         output.extend(name_as_bytes);
-        output.write_u8(self.monster_type.as_u8())?;
+        output.extend(self.monster_type.as_bytes()?);
+        output.write_u8(self.level)?;
         output.write_u32::<LittleEndian>(self.hp)?;
         output.write_u32::<LittleEndian>(self.max_hp)?;
+        output.write_u16::<LittleEndian>(self.speed)?;
+        output.write_u8(self.state.as_u8())?;
         output.extend(self.affinity.as_bytes()?);
         output.extend(self.acceptance.as_bytes()?);
         output.extend(self.status.as_bytes()?);
+        // 0 for ordinary monsters, otherwise one past the boss's phase
+        output.write_u8(self.phase.map_or(0, |phase| phase + 1))?;
         Ok(output)
     }
 }
@@ -147,14 +188,38 @@ impl Outputable for Battle {
         let enemies_as_bytes = self.active_enemies.as_bytes()?;
         let cast_wizard_spells_as_bytes = self.cast_wizard_spells.as_bytes()?;
         let used_monster_abilities_as_bytes = self.used_monster_abilities.as_bytes()?;
+        let spell_cooldowns_as_bytes = self.spell_cooldowns.as_bytes()?;
+        let ally_rows_as_bytes = self.ally_rows.as_bytes()?;
+        let enemy_rows_as_bytes = self.enemy_rows.as_bytes()?;
+        let waves_as_bytes = self.waves.as_bytes()?;
+        let threat_as_bytes = self.threat.as_bytes()?;
+        let hp_window_as_bytes = self.hp_window.as_bytes()?;
         let ticks = self.past_ticks.as_bytes()?;
+        let opening_allies_as_bytes = self.opening_allies.as_bytes()?;
+        let opening_enemies_as_bytes = self.opening_enemies.as_bytes()?;
         let mut output = Vec::with_capacity(
-            allies_as_bytes.len() + enemies_as_bytes.len() + cast_wizard_spells_as_bytes.len() + used_monster_abilities_as_bytes.len() + ticks.len());
+            allies_as_bytes.len() + enemies_as_bytes.len() + cast_wizard_spells_as_bytes.len() + used_monster_abilities_as_bytes.len()
+            + spell_cooldowns_as_bytes.len() + ally_rows_as_bytes.len() + enemy_rows_as_bytes.len() + waves_as_bytes.len() + threat_as_bytes.len() + std::mem::size_of::<u8>()
+            + std::mem::size_of::<u16>() * 2 + hp_window_as_bytes.len() + ticks.len()
+            + opening_allies_as_bytes.len() + opening_enemies_as_bytes.len() + std::mem::size_of::<u64>() * 2);
         output.extend(allies_as_bytes);
         output.extend(enemies_as_bytes);
         output.extend(cast_wizard_spells_as_bytes);
         output.extend(used_monster_abilities_as_bytes);
+        output.extend(spell_cooldowns_as_bytes);
+        output.extend(ally_rows_as_bytes);
+        output.extend(enemy_rows_as_bytes);
+        output.extend(waves_as_bytes);
+        output.extend(threat_as_bytes);
+        output.write_u8(self.variance as u8)?;
+        output.write_u16::<LittleEndian>(self.tick_limit)?;
+        output.write_u16::<LittleEndian>(self.stalemate_ticks)?;
+        output.extend(hp_window_as_bytes);
         output.extend(ticks);
+        output.extend(opening_allies_as_bytes);
+        output.extend(opening_enemies_as_bytes);
+        output.write_u64::<LittleEndian>(self.seed)?;
+        output.write_u64::<LittleEndian>(self.rng.state())?;
         Ok(output)
     }
 }
@@ -175,6 +240,8 @@ impl Outputable for BattleEvent {
             }
             BattleEvent::Victory => { Ok(vec![2]) },
             BattleEvent::Defeat => { Ok(vec![3]) },
+            BattleEvent::Retreat => { Ok(vec![4]) },
+            BattleEvent::Draw => { Ok(vec![5]) },
         }
     }
 }
@@ -241,6 +308,12 @@ impl Outputable for BattleAtom {
                             Status::Hardened => 6,
                             Status::Fluid => 7,
                             Status::Flying => 8,
+                            Status::Poisoned => 14,
+                            Status::Frozen => 15,
+                            Status::Silenced => 16,
+                            Status::Taunting => 17,
+                            Status::Invisible => 18,
+                            Status::Regenerating => 19,
                             Status::Barrier(glyph) => match glyph {
                                 Glyph::Fire => 9,
                                 Glyph::Water => 10,
@@ -266,6 +339,12 @@ impl Outputable for BattleAtom {
                             Status::Hardened => 6,
                             Status::Fluid => 7,
                             Status::Flying => 8,
+                            Status::Poisoned => 14,
+                            Status::Frozen => 15,
+                            Status::Silenced => 16,
+                            Status::Taunting => 17,
+                            Status::Invisible => 18,
+                            Status::Regenerating => 19,
                             Status::Barrier(glyph) => match glyph {
                                 Glyph::Fire => 9,
                                 Glyph::Water => 10,
@@ -282,18 +361,133 @@ impl Outputable for BattleAtom {
                 output.extend_from_slice(&killer.to_le_bytes());
                 output.extend_from_slice(&killee.to_le_bytes());
             }
+            BattleAtom::Initiative(combatant, initiative) => {
+                output.write_u8(9)?;
+                output.extend_from_slice(&combatant.to_le_bytes());
+                output.write_u16::<LittleEndian>(*initiative)?;
+            }
+            BattleAtom::MindShift(combatant, mindset) => {
+                output.write_u8(10)?;
+                output.extend_from_slice(&combatant.to_le_bytes());
+                output.write_u8(mindset.as_u8())?;
+            }
+            BattleAtom::Flee(fled) => {
+                output.write_u8(11)?;
+                output.extend_from_slice(&fled.to_le_bytes());
+            }
+            BattleAtom::Reaction(caster, target, reaction) => {
+                output.write_u8(12)?;
+                output.extend_from_slice(&caster.to_le_bytes());
+                output.extend_from_slice(&target.to_le_bytes());
+                output.write_u8(reaction.as_u8())?;
+            }
+            BattleAtom::OutOfMana(caster) => {
+                output.write_u8(13)?;
+                output.extend_from_slice(&caster.to_le_bytes());
+            }
+            BattleAtom::Interrupted(caster, spell) => {
+                output.write_u8(14)?;
+                output.extend_from_slice(&caster.to_le_bytes());
+                output.extend(spell.as_bytes()?);
+            }
+            BattleAtom::ChangeRow(wizard, row) => {
+                output.write_u8(15)?;
+                output.extend_from_slice(&wizard.to_le_bytes());
+                output.write_u8(row.as_u8())?;
+            }
+            BattleAtom::Summon(summoner, kind, level) => {
+                output.write_u8(16)?;
+                output.extend_from_slice(&summoner.to_le_bytes());
+                output.extend(kind.as_bytes()?);
+                output.write_u8(*level)?;
+            }
+            BattleAtom::Reinforce(kind, level) => {
+                output.write_u8(17)?;
+                output.extend(kind.as_bytes()?);
+                output.write_u8(*level)?;
+            }
+            BattleAtom::Crit(caster, target) => {
+                output.write_u8(18)?;
+                output.extend_from_slice(&caster.to_le_bytes());
+                output.extend_from_slice(&target.to_le_bytes());
+            }
+            BattleAtom::Miss(caster, target) => {
+                output.write_u8(19)?;
+                output.extend_from_slice(&caster.to_le_bytes());
+                output.extend_from_slice(&target.to_le_bytes());
+            }
+            BattleAtom::PhaseChange(boss, phase) => {
+                output.write_u8(20)?;
+                output.extend_from_slice(&boss.to_le_bytes());
+                output.write_u8(*phase)?;
+            }
+            BattleAtom::Immune(caster, target, status) => {
+                output.write_u8(21)?;
+                output.extend_from_slice(&caster.to_le_bytes());
+                output.extend_from_slice(&target.to_le_bytes());
+                output.write_u8(status.as_u8())?;
+            }
         }
         Ok(output)
     }
 }
 
 
+impl Outputable for Combatant {
+    fn as_bytes(&self) -> Result<Vec<u8>> {
+        let name_as_bytes = <String as Outputable>::as_bytes(&self.name)?;
+        let status_as_bytes = self.status.as_bytes()?;
+        let mut output = Vec::with_capacity(
+            name_as_bytes.len()
+            + std::mem::size_of::<u32>()
+            + std::mem::size_of::<u32>()
+            + status_as_bytes.len());
+        output.extend(name_as_bytes);
+        output.write_u32::<LittleEndian>(self.hp)?;
+        output.write_u32::<LittleEndian>(self.max_hp)?;
+        output.extend(status_as_bytes);
+        Ok(output)
+    }
+}
+
+impl Outputable for Replay {
+    fn as_bytes(&self) -> Result<Vec<u8>> {
+        let mut output = Vec::new();
+        output.extend_from_slice(REPLAY_MAGIC);
+        output.write_u8(REPLAY_VERSION)?;
+        output.write_u64::<LittleEndian>(self.seed)?;
+        output.extend(self.allies.as_bytes()?);
+        output.extend(self.enemies.as_bytes()?);
+        output.extend(self.ticks.as_bytes()?);
+        Ok(output)
+    }
+}
+
 impl Outputable for Party {
     fn as_bytes(&self) -> Result<Vec<u8>> {
         Ok(self.uuid.as_bytes().to_vec())
     }
 }
 
+impl Outputable for Row {
+    fn as_bytes(&self) -> Result<Vec<u8>> {
+        Ok(vec![self.as_u8()])
+    }
+}
+
+impl Outputable for Wave {
+    fn as_bytes(&self) -> Result<Vec<u8>> {
+        let mut output = Vec::new();
+        output.write_u16::<LittleEndian>(self.tick)?;
+        output.extend_from_slice(&self.monsters.len().to_le_bytes());
+        for (kind, level) in self.monsters.iter() {
+            output.extend(kind.as_bytes()?);
+            output.write_u8(*level)?;
+        }
+        Ok(output)
+    }
+}
+
 impl Outputable for Glyph {
     fn as_bytes(&self) -> Result<Vec<u8>> {
         Ok(vec![self.as_u8()])
@@ -308,7 +502,11 @@ impl Outputable for Style {
 
 impl Outputable for MonsterType {
     fn as_bytes(&self) -> Result<Vec<u8>> {
-        Ok(vec![self.as_u8()])
+        let mut output = vec![self.as_u8()];
+        if let MonsterType::Custom(name) = self {
+            output.extend(<String as Outputable>::as_bytes(&name.to_string())?);
+        }
+        Ok(output)
     }
 }
 
@@ -354,6 +552,12 @@ impl Outputable for Status {
             Status::Barrier(Glyph::Earth) => { output.write_u32::<LittleEndian>(11)?; },
             Status::Barrier(Glyph::Air) => { output.write_u32::<LittleEndian>(12)?; },
             Status::Barrier(Glyph::Void) => { output.write_u32::<LittleEndian>(13)?; },
+            Status::Poisoned => { output.write_u32::<LittleEndian>(14)?; },
+            Status::Frozen => { output.write_u32::<LittleEndian>(15)?; },
+            Status::Silenced => { output.write_u32::<LittleEndian>(16)?; },
+            Status::Taunting => { output.write_u32::<LittleEndian>(17)?; },
+            Status::Invisible => { output.write_u32::<LittleEndian>(18)?; },
+            Status::Regenerating => { output.write_u32::<LittleEndian>(19)?; },
         }
         Ok(output)
     }
@@ -391,6 +595,12 @@ impl Outputable for BattleMut {
                     Status::Fluid => 7,
                     Status::Flying => 8,
                     Status::Barrier(glyph) => 8 + glyph.as_u8(),
+                    Status::Poisoned => 14,
+                    Status::Frozen => 15,
+                    Status::Silenced => 16,
+                    Status::Taunting => 17,
+                    Status::Invisible => 18,
+                    Status::Regenerating => 19,
                 });
                 output.write_u16::<LittleEndian>(*value)?;
                 output.write_u16::<LittleEndian>(*duration)?;
@@ -406,37 +616,46 @@ impl Outputable for BattleMut {
     }
 }
 
+impl Outputable for Wards {
+    fn as_bytes(&self) -> Result<Vec<u8>> {
+        let mut output = Vec::with_capacity(std::mem::size_of::<u8>() * 2 * (1 + self.statuses.len() + self.glyphs.len()));
+        output.write_u8(self.statuses.len() as u8)?;
+        for (status, percent) in self.statuses.iter() {
+            output.write_u8(status.as_u8())?;
+            output.write_u8(*percent)?;
+        }
+        output.write_u8(self.glyphs.len() as u8)?;
+        for (glyph, percent) in self.glyphs.iter() {
+            output.write_u8(glyph.as_u8())?;
+            output.write_u8(*percent)?;
+        }
+        Ok(output)
+    }
+}
+
 impl Outputable for StatusSet {
     fn as_bytes(&self) -> Result<Vec<u8>> {
-        let mut output = Vec::with_capacity(std::mem::size_of::<u16>()*14*2);
-        output.write_u16::<LittleEndian>(self.barrier_fire.0)?;
-        output.write_u16::<LittleEndian>(self.barrier_fire.1)?;
-        output.write_u16::<LittleEndian>(self.barrier_air.0)?;
-        output.write_u16::<LittleEndian>(self.barrier_air.1)?;
-        output.write_u16::<LittleEndian>(self.barrier_earth.0)?;
-        output.write_u16::<LittleEndian>(self.barrier_earth.1)?;
-        output.write_u16::<LittleEndian>(self.barrier_water.0)?;
-        output.write_u16::<LittleEndian>(self.barrier_water.1)?;
-        output.write_u16::<LittleEndian>(self.barrier_void.0)?;
-        output.write_u16::<LittleEndian>(self.barrier_void.1)?;
-        output.write_u16::<LittleEndian>(self.burning.0)?;
-        output.write_u16::<LittleEndian>(self.burning.1)?;
-        output.write_u16::<LittleEndian>(self.stunned.0)?;
-        output.write_u16::<LittleEndian>(self.stunned.1)?;
-        output.write_u16::<LittleEndian>(self.submerged.0)?;
-        output.write_u16::<LittleEndian>(self.submerged.1)?;
-        output.write_u16::<LittleEndian>(self.shocked.0)?;
-        output.write_u16::<LittleEndian>(self.shocked.1)?;
-        output.write_u16::<LittleEndian>(self.weakened.0)?;
-        output.write_u16::<LittleEndian>(self.weakened.1)?;
-        output.write_u16::<LittleEndian>(self.raging.0)?;
-        output.write_u16::<LittleEndian>(self.raging.1)?;
-        output.write_u16::<LittleEndian>(self.hardened.0)?;
-        output.write_u16::<LittleEndian>(self.hardened.1)?;
-        output.write_u16::<LittleEndian>(self.fluid.0)?;
-        output.write_u16::<LittleEndian>(self.fluid.1)?;
-        output.write_u16::<LittleEndian>(self.flying.0)?;
-        output.write_u16::<LittleEndian>(self.flying.1)?;
+        // A count of statuses, then for each a count of instances, each its value, duration and whether
+        // it has a source, followed by the source.
+        let instance_bytes = |instance: &StatusInstance| 2 + 2 + 1 + instance.source.map_or(0, |_| std::mem::size_of::<usize>());
+        let mut output = Vec::with_capacity(1 + self.slots.iter()
+            .map(|instances| 1 + instances.iter().map(instance_bytes).sum::<usize>())
+            .sum::<usize>());
+        output.write_u8(self.slots.len() as u8)?;
+        for instances in self.slots.iter() {
+            output.write_u8(instances.len() as u8)?;
+            for instance in instances.iter() {
+                output.write_u16::<LittleEndian>(instance.value)?;
+                output.write_u16::<LittleEndian>(instance.duration)?;
+                match instance.source {
+                    Some(source) => {
+                        output.write_u8(1)?;
+                        output.extend_from_slice(&source.to_le_bytes());
+                    }
+                    None => output.write_u8(0)?,
+                }
+            }
+        }
         Ok(output)
     }
 }
@@ -461,7 +680,7 @@ impl Outputable for Spell {
         output.write_u16::<LittleEndian>(self.glyph.1)?;
         output.write_u8(self.style.0.as_u8())?;
         output.write_u16::<LittleEndian>(self.style.1)?;
-        output.extend(spells::ID_BY_NAME[self.name].as_bytes()?);
+        output.extend(<String as Outputable>::as_bytes(&self.name.to_string())?);
         output.extend(self.ability.as_bytes()?);
         Ok(output)
     }
@@ -476,53 +695,31 @@ impl Outputable for PriorityTypes {
         })
     }
 }
-impl PriorityType {
-    fn as_u8(&self) -> u8 {
-        match self {
-            PriorityType::Squishy => 0,
-            PriorityType::Tanky => 1,
-            PriorityType::LowHealth => 2,
-            PriorityType::HighHealth => 3,
-            PriorityType::HasStatus(status) => 4 + status.as_u8(),
-            PriorityType::NoStatus(status) => 18 + status.as_u8(),
-        }
-    }
-}
-
-impl Outputable for EffectProgression {
+impl Outputable for EffectStep {
     fn as_bytes(&self) -> Result<Vec<u8>> {
-        match self {
-            EffectProgression::None => {
-                Ok(vec![0])
-            }
-            EffectProgression::Single(effect) => {
-                let effect = effect.as_bytes()?;
-                let mut output = Vec::with_capacity(1 + effect.len());
+        let mut output = self.effect.as_bytes()?;
+        match &self.target {
+            Some(target) => {
                 output.write_u8(1)?;
-                output.extend(effect);
-                Ok(output)
+                output.extend(target.as_2u8());
             }
-            EffectProgression::Duo(effect1, effect2) => {
-                let effect1 = effect1.as_bytes()?;
-                let effect2 = effect2.as_bytes()?;
-                let mut output = Vec::with_capacity(1 + effect1.len() + effect2.len());
-                output.write_u8(2)?;
-                output.extend(effect1);
-                output.extend(effect2);
-                Ok(output)
+            None => output.write_u8(0)?,
+        }
+        match &self.condition {
+            Some(condition) => {
+                output.write_u8(1)?;
+                output.write_u8(condition.as_u8())?;
             }
-            EffectProgression::Trio(effect1, effect2, effect3) => {
-                let effect1 = effect1.as_bytes()?;
-                let effect2 = effect2.as_bytes()?;
-                let effect3 = effect3.as_bytes()?;
-                let mut output = Vec::with_capacity(1 + effect1.len() + effect2.len() + effect3.len());
-                output.write_u8(3)?;
-                output.extend(effect1);
-                output.extend(effect2);
-                output.extend(effect3);
-                Ok(output)
+            None => output.write_u8(0)?,
+        }
+        match &self.otherwise {
+            Some(effect) => {
+                output.write_u8(1)?;
+                output.extend(effect.as_bytes()?);
             }
+            None => output.write_u8(0)?,
         }
+        Ok(output)
     }
 }
 
@@ -542,12 +739,14 @@ impl Outputable for Ability {
     fn as_bytes(&self) -> Result<Vec<u8>> {
         let priority = self.priority.as_bytes()?;
         let target = self.target.as_2u8();
-        let effects = self.effects.as_bytes()?;
 
-        let mut output = Vec::with_capacity(priority.len() + target.len() + effects.len());
+        let mut output = Vec::with_capacity(priority.len() + target.len() + 1);
         output.extend(priority);
         output.extend(target);
-        output.extend(effects);
+        output.write_u8(self.effects.len() as u8)?;
+        for step in self.effects.iter() {
+            output.extend(step.as_bytes()?);
+        }
         Ok(output)
     }
 }
@@ -597,6 +796,11 @@ impl Outputable for EffectApplication {
                 output.write_u8(3)?;
                 output.write_u8(status.as_u8())?;
             },
+            EffectApplication::Summon(kind, level) => {
+                output.write_u8(4)?;
+                output.extend(kind.as_bytes()?);
+                output.write_u8(*level)?;
+            },
         }
         Ok(output)
     }
@@ -609,26 +813,11 @@ impl TargetType {
             TargetType::MeAlone => [0,0],
             TargetType::Ally(index) => [1,*index],
             TargetType::Enemy(index) => [2,*index],
-        }
-    }
-}
-impl Status {
-    pub fn as_u8(&self) -> u8 {
-        match self {
-            Status::Barrier(Glyph::Fire) => 0,
-            Status::Barrier(Glyph::Water) => 1,
-            Status::Barrier(Glyph::Earth) => 2,
-            Status::Barrier(Glyph::Air) => 3,
-            Status::Barrier(Glyph::Void) => 4,
-            Status::Burning => 5,
-            Status::Stunned => 6,
-            Status::Submerged => 7,
-            Status::Shocked => 8,
-            Status::Weakened => 9,
-            Status::Raging => 10,
-            Status::Hardened => 11,
-            Status::Fluid => 12,
-            Status::Flying => 13,
+            TargetType::AllAllies => [3,0],
+            TargetType::AllEnemies => [4,0],
+            TargetType::Everyone => [5,0],
+            TargetType::RandomEnemy(count) => [6,*count],
+            TargetType::Splash => [7,0],
         }
     }
 }
@@ -1,22 +1,29 @@
 
 
-pub mod c;
 mod input;
-pub mod q;
-pub mod realms;
+
+pub use tavern_keeper::a::{c, q, realms, rng};
 
 use crate::a::c::Colosseum;
 use crate::g::render_gl::Viewport;
 use crate::g::resources::Resources;
 use sdl2::EventPump;
 
+use rand::RngCore;
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::time::Instant;
 use q::battle::Battle;
 use c::e::wiz::Wizard;
 use c::e::mon::Monster;
+use c::e::spell::spells;
 
 use self::c::e::mon::MonsterType;
-use self::q::battle::{Tick, BattleEvent};
+use self::q::battle::Tick;
+use self::q::observer::LogObserver;
+use self::q::replay::Replay;
+
+const LAST_REPLAY_FILENAME: &str = "./assets/last.replay";
 
 pub struct GameState {
     pub clock: Instant,
@@ -30,6 +37,7 @@ pub struct GameState {
 
 pub fn setup() -> Result<GameState, failure::Error> {
     let res = Resources::from_relative_exe_path("assets").unwrap();
+    c::e::mon::load()?;
 
     let viewport = Viewport::for_window(900, 700);
     Ok(GameState {
@@ -63,22 +71,25 @@ impl GameState {
         if let None = self.battle {
             let wiz_id = self.col.insert(Wizard::new("Bob".to_string()));
             let wiz: &mut Wizard = self.col.get_mut(wiz_id);
-            wiz.add_spell_to_book(c::e::spell::spells::fire::FIREBALL.clone());
+            wiz.add_spell_to_book(spells::by_name("fireball").ok_or_else(|| failure::err_msg("Unknown spell: fireball"))?);
             
             let wiz_id = self.col.insert(Wizard::new("Rob".to_string()));
             let wiz: &mut Wizard = self.col.get_mut(wiz_id);
-            wiz.add_spell_to_book(c::e::spell::spells::air::LIGHTNING.clone());
+            wiz.add_spell_to_book(spells::by_name("lightning").ok_or_else(|| failure::err_msg("Unknown spell: lightning"))?);
 
             let mon_id = self.col.insert(Monster::new("Tod", &MonsterType::Goblin, 1));
             let mon_id2 = self.col.insert(Monster::new("Sod", &MonsterType::Goblin, 1));
             let mon_id3 = self.col.insert(Monster::new("Vod", &MonsterType::Goblin, 1));
 
-            self.battle = Some(Battle::new(vec![wiz_id], vec![mon_id, mon_id2, mon_id3]));
+            let seed = self.col.rng_mut().next_u64();
+            self.battle = Some(Battle::new(vec![wiz_id], vec![mon_id, mon_id2, mon_id3], seed)
+                .with_observer(Rc::new(RefCell::new(LogObserver))));
         }
         self.tick = self.battle.as_mut().unwrap().tick(&mut self.tick, &mut self.col);
-        if self.tick.iter().any(|event| event == &BattleEvent::Victory || event == &BattleEvent::Defeat) {
+        if self.tick.iter().any(|event| event.is_outcome()) {
             self.col.insert(self.battle.clone().unwrap());
             self.col.save().unwrap();
+            Replay::from_battle(self.battle.as_ref().unwrap()).save(LAST_REPLAY_FILENAME)?;
             return Ok(false);
         }
         Ok(true)
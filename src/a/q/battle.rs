@@ -1,8 +1,31 @@
 use crate::a::c::{Colosseum, ColosseumArena, Idable};
-use crate::a::c::e::{spell::{Spell, Effect, PriorityType, TargetType, EffectApplication, PriorityTypes}, wiz::{Wizard, Affinity,}, status::{Status, StatusSet}, mon::Monster, Glyph,};
-use std::{collections::HashMap, cmp::Ordering, usize};
+use crate::a::rng::GameRng;
+use crate::a::q::replay::Combatant;
+use crate::a::q::observer::{self, BattleObserver, Observers};
+use crate::a::c::e::{spell::{Spell, Effect, EffectStep, PriorityType, TargetType, EffectApplication, PriorityTypes}, wiz::{Wizard, Affinity, Acceptance, MindSet, MANA_REGEN}, status::{Status, StatusSet, OnTick, Wards}, mon::{Monster, MonsterType}, boss::PhaseShift, party::{Party, Row}, reaction::Reaction, Glyph,};
+use std::{collections::HashMap, cmp::Ordering, rc::Rc, cell::RefCell};
 use generational_arena::Index;
+use rand::Rng;
+use rand::seq::SliceRandom;
 pub type Tick = Vec<BattleEvent>;
+
+// Splash damage on the neighbours of the target is this many times weaker.
+const SPLASH_FALLOFF: u16 = 2;
+// How much a monster's spell choice leans towards what it can do to its biggest threat.
+const THREAT_FOCUS: f32 = 0.5;
+// With variance on: percent chances, then the range damage is rolled in, in percent of itself.
+const CRIT_CHANCE: i32 = 5;
+const CRIT_PER_AFFINITY: i32 = 2;
+const CRIT_MULTIPLIER: u16 = 2;
+const DODGE_PER_POINT: i32 = 3; // of Flying and Fluid on the target
+const MAX_CHANCE: i32 = 50;
+const DAMAGE_ROLL: (u16, u16) = (85, 116);
+// Calls for help go unanswered once this many stand on the caller's side.
+pub const SUMMON_CAP: usize = 8;
+// A battle is called a draw once it has gone on this long, or no one's hp has moved for this many ticks.
+pub const TICK_LIMIT: u16 = 500;
+pub const STALEMATE_TICKS: u16 = 30;
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum BattleAtom {
     Kill(usize, usize), // Killer, Killed
@@ -11,6 +34,19 @@ pub enum BattleAtom {
     TickEffect(usize, Spell, u8, u32), // caster, spell,index of effect, progress
     SpellEnd(usize, Spell),
     FizzleSpell(usize, Spell),
+    Initiative(usize, u16), // combatant, initiative; pushed in the order turns are taken
+    MindShift(usize, MindSet),
+    Flee(usize), // escaped; leaves the battle alive at the start of the next tick
+    Reaction(usize, usize, Reaction), // caster, target; followed by the mutations it causes
+    OutOfMana(usize), // wizard who could afford none of their spells and lets the turn pass
+    Interrupted(usize, Spell), // a channelled step broken off by a stun or a shock
+    ChangeRow(usize, Row), // wizard spending the turn moving to the other row
+    Crit(usize, usize), // caster, target; the damage right after it is multiplied
+    Miss(usize, usize), // caster, target; dodged, so the effect doesn't land
+    Summon(usize, MonsterType, u8), // summoner, kind, level; joins the summoner's side once the tick is over
+    Reinforce(MonsterType, u8), // kind, level; one of a scheduled wave joining the enemy at the start of the tick
+    PhaseChange(usize, u8), // boss, phase it enters; followed by the statuses and summons that come with it
+    Immune(usize, usize, Status), // caster, target; the status is warded off entirely
 }
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum BattleMut {
@@ -35,6 +71,21 @@ pub enum BattleEvent {
     Wizard(BattleAtom),
     Victory,
     Defeat,
+    Retreat,
+    Draw, // out of time, or no one's hp moved for too long
+}
+
+impl BattleEvent {
+    pub fn is_outcome(&self) -> bool {
+        matches!(self, BattleEvent::Victory | BattleEvent::Defeat | BattleEvent::Retreat | BattleEvent::Draw)
+    }
+}
+
+// Monsters that join the enemy once the battle has run for `tick` ticks.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Wave {
+    pub tick: u16,
+    pub monsters: Vec<(MonsterType, u8)>, // kind, level
 }
 
 #[derive(Clone, Debug)]
@@ -46,7 +97,21 @@ pub struct Battle {
     pub enemies: Vec<Index>, // index points to Arena<Monster>[Index]
     pub active_enemies: Vec<usize>,
     pub used_monster_abilities: HashMap<usize,Spell>, // index points to Arena<Monster>[Index]
+    pub spell_cooldowns: HashMap<usize, HashMap<String, u16>>, // wizard index -> spell name -> ticks left
+    pub ally_rows: Vec<Row>, // same order as allies
+    pub enemy_rows: Vec<Row>, // same order as enemies
+    pub waves: Vec<Wave>, // still to come, earliest first
+    pub threat: HashMap<usize, HashMap<usize, u32>>, // monster index -> wizard index -> threat
+    pub variance: bool, // crits, dodges and damage rolls; off leaves every hit exact
+    pub tick_limit: u16,
+    pub stalemate_ticks: u16,
+    pub hp_window: Vec<Vec<u32>>, // allies then enemies, one snapshot per tick over the last stalemate_ticks, oldest first
     pub past_ticks: Vec<Tick>,
+    pub opening_allies: Vec<Combatant>,
+    pub opening_enemies: Vec<Combatant>,
+    pub seed: u64,
+    pub rng: GameRng,
+    pub observers: Observers,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -62,16 +127,40 @@ pub enum TargetSqiushy {
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Target {
     pub index: usize,
+    pub hp: u32,
+    pub max_hp: u32,
+    pub initiative: u16,
+    pub mindset: MindSet,
     pub health: TargetHealth,
     pub squishy: TargetSqiushy,
     pub statuses: StatusSet,
+    pub row: Row,
+    pub threat: u32, // towards the monster whose turn it is; 0 for anyone else
+    pub wards: Wards,
     pub augment: Affinity,
     pub augment_cast: Affinity,
     pub resist: Affinity,
 }
 
+// Who is casting what, as a step of it resolves.
+pub struct Casting<'a, T> {
+    pub caster: &'a T,
+    pub as_target: &'a Target,
+    pub spell: &'a Spell,
+}
+
+// What a step resolves against: the caster's side and the other one, where the atoms it
+// makes go, and the dice it rolls with when the battle has variance.
+pub struct Battlefield<'a> {
+    pub battle_ticks: &'a mut Tick,
+    pub allies: &'a mut Vec<Target>,
+    pub enemies: &'a mut Vec<Target>,
+    pub rng: &'a mut GameRng,
+    pub variance: bool,
+}
+
 impl Battle {
-    pub fn new(allies: Vec<Index>, enemies: Vec<Index>) -> Self {
+    pub fn new(allies: Vec<Index>, enemies: Vec<Index>, seed: u64) -> Self {
         let allies_len = allies.len();
         let enemies_len = enemies.len();
         Self {
@@ -82,10 +171,56 @@ impl Battle {
             enemies: enemies,
             active_enemies: (0..enemies_len).into_iter().collect::<Vec<usize>>(),
             used_monster_abilities: HashMap::new(),
+            spell_cooldowns: HashMap::new(),
+            ally_rows: Vec::new(),
+            enemy_rows: Vec::new(),
+            waves: Vec::new(),
+            threat: HashMap::new(),
+            variance: false,
+            tick_limit: TICK_LIMIT,
+            stalemate_ticks: STALEMATE_TICKS,
+            hp_window: Vec::new(),
             past_ticks: Vec::new(),
+            opening_allies: Vec::new(),
+            opening_enemies: Vec::new(),
+            seed,
+            rng: GameRng::new(seed),
+            observers: Observers::new(),
         }
     }
 
+    // Wizards line up the way their party is set up; without one they all stand in front.
+    pub fn in_formation(mut self, party: &Party) -> Self {
+        self.ally_rows = self.allies.iter().map(|ally| party.row(ally)).collect();
+        self
+    }
+
+    pub fn with_waves(mut self, mut waves: Vec<Wave>) -> Self {
+        waves.sort_by_key(|wave| wave.tick);
+        self.waves = waves;
+        self
+    }
+
+    pub fn with_variance(mut self) -> Self {
+        self.variance = true;
+        self
+    }
+
+    pub fn with_tick_limit(mut self, tick_limit: u16) -> Self {
+        self.tick_limit = tick_limit;
+        self
+    }
+
+    pub fn with_stalemate_ticks(mut self, stalemate_ticks: u16) -> Self {
+        self.stalemate_ticks = stalemate_ticks;
+        self
+    }
+
+    pub fn with_observer(mut self, observer: Rc<RefCell<dyn BattleObserver>>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
     pub fn push_ally(&mut self, ally: Index) {
         self.allies.push(ally);
     }
@@ -97,26 +232,38 @@ impl Battle {
         format!("{:#?}", self.past_ticks)
     }
    
-    pub fn run(mut self, col: &mut Colosseum) {
+    pub fn run(&mut self, col: &mut Colosseum) -> BattleEvent {
         let mut tick = Vec::new();
         loop {
             tick = self.tick(&mut tick, col);
-            if tick.iter().any(|event| event == &BattleEvent::Victory || event == &BattleEvent::Defeat) {
-                break;
+            if let Some(outcome) = tick.iter().find(|event| event.is_outcome()) {
+                return outcome.clone();
             }
         }
     }
 
     pub fn tick(&mut self, currect_tick: &mut Tick, col: &mut Colosseum) -> Tick {
         let mut tick = Vec::new();
+        let arrivals;
+
+        if self.past_ticks.is_empty() {
+            self.opening_allies = self.allies.iter()
+                .map(|id| (col as &dyn ColosseumArena<Wizard>).get(*id).as_combatant())
+                .collect::<Vec<Combatant>>();
+            self.opening_enemies = self.enemies.iter()
+                .map(|id| (col as &dyn ColosseumArena<Monster>).get(*id).as_combatant())
+                .collect::<Vec<Combatant>>();
+        }
 
         {
             let mut killed_allies = Vec::new();
             let mut killed_enemies = Vec::new();
             for event in currect_tick.iter() {
                 match event {
-                    BattleEvent::Wizard(BattleAtom::Kill(_killer, killed)) => killed_allies.push(killed),
-                    BattleEvent::Monster(BattleAtom::Kill(_killer, killed)) => killed_enemies.push(killed),
+                    BattleEvent::Wizard(BattleAtom::Kill(_, killed))
+                    | BattleEvent::Wizard(BattleAtom::Flee(killed)) => killed_allies.push(killed),
+                    BattleEvent::Monster(BattleAtom::Kill(_, killed))
+                    | BattleEvent::Monster(BattleAtom::Flee(killed)) => killed_enemies.push(killed),
                     _ => {}
                 }
             }
@@ -134,28 +281,49 @@ impl Battle {
                     .collect::<Vec<usize>>();
             }
 
-            if self.active_allies.len() == 0 {
-                self.past_ticks.push(currect_tick.clone());
-                tick.push(BattleEvent::Defeat);
-                self.past_ticks.push(tick.clone());
+            if self.active_allies.is_empty() {
+                self.record(currect_tick.clone());
+                if self.fled_allies().is_empty() {
+                    tick.push(BattleEvent::Defeat);
+                } else {
+                    tick.push(BattleEvent::Retreat);
+                }
+                self.record(tick.clone());
                 return tick;
             }
 
-            if self.active_enemies.len() == 0 {
-                self.past_ticks.push(currect_tick.clone());
+            arrivals = self.call_reinforcements(col);
+
+            if self.active_enemies.is_empty() {
+                self.record(currect_tick.clone());
                 tick.push(BattleEvent::Victory);
-                self.past_ticks.push(tick.clone());
+                self.record(tick.clone());
+                return tick;
+            }
+
+            // Only a fight neither side has won can be called; whoever just came in still counts as there.
+            if self.is_drawn(col) {
+                currect_tick.extend(arrivals);
+                self.record(currect_tick.clone());
+                tick.push(BattleEvent::Draw);
+                self.record(tick.clone());
                 return tick;
             }
         }
 
-        
+
+        let mut battle_ticks = arrivals;
+        self.fill_rows(col);
+        self.shift_mindsets(currect_tick, col, &mut battle_ticks);
+
         let mut wizards_as_targets = self.active_allies.iter()
             .map(|index| col.as_target::<Wizard>(self.allies[*index], *index))
             .collect::<Vec<Target>>();
         let mut monsters_as_targets = self.active_enemies.iter()
                 .map(|index| col.as_target::<Monster>(self.enemies[*index], *index))
                 .collect::<Vec<Target>>();
+        let order = Self::initiative_order(&wizards_as_targets, &monsters_as_targets);
+        self.cool_down();
         {
             for event in currect_tick.iter() {
                 match event {
@@ -167,70 +335,153 @@ impl Battle {
                         self.used_monster_abilities.insert(*index, spell.clone());
                         tick.push(BattleEvent::Monster(BattleAtom::TickEffect(index.clone(), spell.clone(), 1, 0)));
                     }
-                    BattleEvent::Wizard(BattleAtom::SpellEnd(index, _spell)) => {
+                    BattleEvent::Wizard(BattleAtom::SpellEnd(index, spell))
+                    | BattleEvent::Wizard(BattleAtom::FizzleSpell(index, spell))
+                    | BattleEvent::Wizard(BattleAtom::Interrupted(index, spell)) => {
                         self.cast_wizard_spells.remove(index);
+                        self.start_cooldown(*index, spell);
                     }
                     BattleEvent::Monster(BattleAtom::SpellEnd(index, _spell)) => {
                         self.used_monster_abilities.remove(index);
                     }
-                    BattleEvent::Wizard(BattleAtom::FizzleSpell(index, _spell)) => {
-                        self.cast_wizard_spells.remove(index);
-                    }
-                    BattleEvent::Monster(BattleAtom::FizzleSpell(index, _spell)) => {
+                    BattleEvent::Monster(BattleAtom::FizzleSpell(index, _spell))
+                    | BattleEvent::Monster(BattleAtom::Interrupted(index, _spell)) => {
                         self.used_monster_abilities.remove(index);
                     }
+                    BattleEvent::Wizard(BattleAtom::ChangeRow(index, row)) => {
+                        self.ally_rows[*index] = row.clone();
+                    }
                     _ => {}
                 }
             }
+            Self::place(&mut wizards_as_targets, &self.ally_rows);
+            Self::place(&mut monsters_as_targets, &self.enemy_rows);
             
-            for wizard in self.active_allies.iter() {
-                if !self.cast_wizard_spells.contains_key(wizard) {
-                    let wiz: &Wizard = col.get(self.allies[*wizard]);
-                    let spells = wiz.get_spells();
-                    let spell = Self::pick_spell(spells, &wizards_as_targets, &monsters_as_targets);
-                    tick.push(BattleEvent::Wizard(BattleAtom::CastSpell(wizard.clone(), spell)));
-                }
-            }
-            for monster in self.active_allies.iter() {
-                if !self.used_monster_abilities.contains_key(monster) {
-                    let mon: &Monster = col.get(self.enemies[*monster]);
-                    let spells = &mon.get_abilities();
-                    let spell = Self::pick_spell(spells, &monsters_as_targets, &wizards_as_targets);
-                    tick.push(BattleEvent::Monster(BattleAtom::CastSpell(monster.clone(), spell)));
+            for turn in order.iter() {
+                match turn {
+                    BattleEvent::Wizard(BattleAtom::Initiative(wizard, _)) if !self.cast_wizard_spells.contains_key(wizard) => {
+                        let wiz: &Wizard = col.get(self.allies[*wizard]);
+                        // Stepping behind a standing ally comes before running for it.
+                        let mut caster = wiz.as_target(*wizard);
+                        caster.row = self.ally_rows[*wizard].clone();
+                        if let Some(row) = Self::wants_to_change_row(&caster, &wizards_as_targets) {
+                            tick.push(BattleEvent::Wizard(BattleAtom::ChangeRow(*wizard, row)));
+                            continue;
+                        }
+                        if Self::wants_to_flee(&wiz.as_target(*wizard)) {
+                            if self.rng.gen_range(0, 100) < Self::flee_chance(&wiz.as_target(*wizard), &monsters_as_targets) {
+                                tick.push(BattleEvent::Wizard(BattleAtom::Flee(*wizard)));
+                            }
+                            continue;
+                        }
+                        let affordable = wiz.affordable_spells();
+                        let ready = affordable.iter()
+                            .filter(|spell| !self.is_cooling_down(wizard, spell))
+                            .filter(|spell| Self::can_cast(&wiz.status, spell))
+                            .cloned()
+                            .collect::<Vec<Spell>>();
+                        match Self::pick_spell(&ready, &wiz.as_target(*wizard), &wizards_as_targets, &monsters_as_targets) {
+                            Some(spell) => {
+                                let wiz: &mut Wizard = col.get_mut(self.allies[*wizard]);
+                                wiz.spend_mana(&spell);
+                                tick.push(BattleEvent::Wizard(BattleAtom::CastSpell(*wizard, spell)));
+                            }
+                            None if affordable.is_empty() => tick.push(BattleEvent::Wizard(BattleAtom::OutOfMana(*wizard))),
+                            None => {} // everything it could pay for is still cooling down
+                        }
+                    }
+                    BattleEvent::Monster(BattleAtom::Initiative(monster, _)) if !self.used_monster_abilities.contains_key(monster) => {
+                        let mon: &Monster = col.get(self.enemies[*monster]);
+                        if Self::wants_to_flee(&mon.as_target(*monster)) {
+                            if self.rng.gen_range(0, 100) < Self::flee_chance(&mon.as_target(*monster), &wizards_as_targets) {
+                                tick.push(BattleEvent::Monster(BattleAtom::Flee(*monster)));
+                            }
+                            continue;
+                        }
+                        let spells = &mon.get_abilities().into_iter()
+                            .filter(|spell| Self::can_cast(&mon.status, spell))
+                            .collect::<Vec<Spell>>();
+                        self.weigh_threat(*monster, &mut wizards_as_targets);
+                        if let Some(spell) = Self::pick_spell(spells, &mon.as_target(*monster), &monsters_as_targets, &wizards_as_targets) {
+                            tick.push(BattleEvent::Monster(BattleAtom::CastSpell(*monster, spell)));
+                        }
+                    }
+                    _ => {}
                 }
             }
         }
-        let mut battle_ticks = Vec::new();
+        battle_ticks.extend(order.iter().cloned());
         {
-            for event in currect_tick.iter() {
-                match event {
-                    BattleEvent::Wizard(BattleAtom::TickEffect(index, spell, effect_index, progress)) => {
-                        let wizard: &Wizard = col.get(self.allies[*index]);
-                        Self::tick_effect(&mut tick, wizard, &wizard.as_target(*index),  &spell, &effect_index, &progress, &mut battle_ticks, &mut wizards_as_targets, &mut monsters_as_targets);
-                    }
-                    BattleEvent::Monster(BattleAtom::TickEffect(index, spell, effect_index, progress)) => {
-                        let monster: &Monster = col.get(self.enemies[*index]);
-                        Self::tick_effect(&mut tick, monster, &monster.as_target(*index),  &spell, &effect_index, &progress, &mut battle_ticks, &mut wizards_as_targets, &mut monsters_as_targets);
+            // Effects resolve fastest first; anyone brought down by a faster combatant
+            // loses their turn and can no longer be picked as a target this tick.
+            for turn in order.iter() {
+                let resolved_from = battle_ticks.len();
+                for event in currect_tick.iter() {
+                    match (turn, event) {
+                        (BattleEvent::Wizard(BattleAtom::Initiative(turn_index, _)), BattleEvent::Wizard(BattleAtom::TickEffect(index, spell, effect_index, progress)))
+                            if turn_index == index && Self::is_standing(&wizards_as_targets, index) => {
+                            let wizard: &Wizard = col.get(self.allies[*index]);
+                            let casting = Casting { caster: wizard, as_target: &wizard.as_target(*index), spell };
+                            Self::tick_effect(&mut tick, &casting, *effect_index, *progress, &mut Battlefield {
+                                battle_ticks: &mut battle_ticks,
+                                allies: &mut wizards_as_targets,
+                                enemies: &mut monsters_as_targets,
+                                rng: &mut self.rng,
+                                variance: self.variance,
+                            });
+                            self.raise_threat(*index, &battle_ticks[resolved_from..]);
+                        }
+                        (BattleEvent::Monster(BattleAtom::Initiative(turn_index, _)), BattleEvent::Monster(BattleAtom::TickEffect(index, spell, effect_index, progress)))
+                            if turn_index == index && Self::is_standing(&monsters_as_targets, index) => {
+                            let monster: &Monster = col.get(self.enemies[*index]);
+                            self.weigh_threat(*index, &mut wizards_as_targets);
+                            let casting = Casting { caster: monster, as_target: &monster.as_target(*index), spell };
+                            Self::tick_effect(&mut tick, &casting, *effect_index, *progress, &mut Battlefield {
+                                battle_ticks: &mut battle_ticks,
+                                allies: &mut monsters_as_targets,
+                                enemies: &mut wizards_as_targets,
+                                rng: &mut self.rng,
+                                variance: self.variance,
+                            });
+                        }
+                        _ => {}
                     }
-                    _ => {}
                 }
+                Self::project(&battle_ticks[resolved_from..], &mut wizards_as_targets, &mut monsters_as_targets);
             }
         }
 
-        for wizard_target in wizards_as_targets {
+        for wizard_target in wizards_as_targets.iter() {
+            let wizard: &Wizard = col.get(self.allies[wizard_target.index]);
+            tick = Self::tick_status(&wizard_target.index, true, &wizard.status, &mut battle_ticks, tick);
+        }
+
+        for monster_target in monsters_as_targets.iter() {
+            let monster: &Monster = col.get(self.enemies[monster_target.index]);
+            tick = Self::tick_status(&monster_target.index, false, &monster.status, &mut battle_ticks, tick);
+        }
+
+        // Only once everyone's damage over time is in can the statuses weigh in on all of this tick's blows.
+        for wizard_target in wizards_as_targets.iter() {
             let wizard: &mut Wizard = col.get_mut(self.allies[wizard_target.index]);
-            tick = Self::tick_wiz_status(&wizard_target.index, &wizard.status, &mut battle_ticks, tick);
+            Self::modify_mutations(&wizard_target.index, true, &wizard.status, &mut battle_ticks);
             wizard.status.tick_all();
+            wizard.restore_mana(MANA_REGEN);
         }
 
-        for monster_target in monsters_as_targets {
+        for monster_target in monsters_as_targets.iter() {
             let monster: &mut Monster = col.get_mut(self.enemies[monster_target.index]);
-            tick = Self::tick_mon_status(&monster_target.index, &monster.status, &mut battle_ticks, tick);
+            Self::modify_mutations(&monster_target.index, false, &monster.status, &mut battle_ticks);
             monster.status.tick_all();
         }
 
         {
             for battle_tick in battle_ticks.into_iter() {
+                if let BattleEvent::Monster(BattleAtom::Summon(..)) = battle_tick {
+                    if !self.can_summon() {
+                        continue;
+                    }
+                }
                 currect_tick.push(battle_tick.clone());
                 match battle_tick {
                     BattleEvent::Wizard(BattleAtom::Mutation(battle_mut)) => {
@@ -240,99 +491,296 @@ impl Battle {
                     }
                     BattleEvent::Monster(BattleAtom::Mutation(battle_mut)) => {
                         //BattleAtom::Damage(damager, damagee, damage, _)
-                        let affectee: &mut Wizard = col.get_mut(self.allies[battle_mut.affectee()]);
+                        let affectee: &mut Monster = col.get_mut(self.enemies[battle_mut.affectee()]);
                         Self::mutate(&battle_mut, affectee, &mut tick);
                     }
+                    // Spells that summon are kept out of wizards' books, so only monsters call for help.
+                    BattleEvent::Monster(BattleAtom::Summon(_summoner, kind, level)) => self.summon(col, &kind, level),
                     _ => {}
                 };
             }
         }
-        self.past_ticks.push(currect_tick.clone());
+        self.advance_phases(col, currect_tick, &mut tick);
+        self.record(currect_tick.clone());
         tick
     }
 
-    fn tick_wiz_status(affectee: &usize, statuses: &StatusSet, battle: &mut Vec<BattleEvent>, tick: Tick) -> Tick {
-        if let Some(s) = statuses.entry(&Status::Burning) {
-            battle.push(BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(*affectee, *affectee, s, Glyph::Fire))));
+    fn record(&mut self, tick: Tick) {
+        for observer in self.observers.iter() {
+            let mut observer = observer.borrow_mut();
+            for event in tick.iter() {
+                observer::notify(&mut *observer, event, self.past_ticks.len());
+            }
+        }
+        self.past_ticks.push(tick);
+    }
+
+    // Out of time, or stuck: no one is worse off than a while ago and no one is left to come in and change that.
+    // The tick being decided and the outcome after it both count against the limit.
+    fn is_drawn(&mut self, col: &Colosseum) -> bool {
+        if self.past_ticks.len() + 2 >= self.tick_limit as usize {
+            return true;
         }
-        if let Some(s) = statuses.entry(&Status::Submerged) {
-            battle.push(BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(*affectee, *affectee, s, Glyph::Water))));
+        let hp = self.allies.iter()
+            .map(|id| (col as &dyn ColosseumArena<Wizard>).get(*id).hp)
+            .chain(self.enemies.iter().map(|id| (col as &dyn ColosseumArena<Monster>).get(*id).hp))
+            .collect::<Vec<u32>>();
+        self.hp_window.push(hp);
+        if self.hp_window.len() > self.stalemate_ticks as usize + 1 {
+            self.hp_window.remove(0);
         }
-        return tick.into_iter().map(|event| {
+        // Blows healed back as fast as they land are no progress; someone joining is.
+        let (oldest, newest) = (&self.hp_window[0], &self.hp_window[self.hp_window.len() - 1]);
+        let stalled = self.hp_window.len() > self.stalemate_ticks as usize
+            && oldest.len() == newest.len()
+            && oldest.iter().zip(newest).all(|(then, now)| now >= then);
+        self.waves.is_empty() && stalled
+    }
+
+    // Bosses knocked below a threshold enter their next phase, one phase at a time if they fell through several.
+    fn advance_phases(&mut self, col: &mut Colosseum, currect_tick: &mut Tick, tick: &mut Tick) {
+        for index in self.active_enemies.clone() {
+            loop {
+                let monster: &mut Monster = col.get_mut(self.enemies[index]);
+                let phase = match monster.next_phase() {
+                    Some(phase) => phase,
+                    None => break,
+                };
+                let entered = monster.phase.unwrap_or(0) + 1;
+                monster.phase = Some(entered);
+                currect_tick.push(BattleEvent::Monster(BattleAtom::PhaseChange(index, entered)));
+                for shift in phase.shifts.iter() {
+                    match shift {
+                        PhaseShift::Status(status, value, duration) => {
+                            let battle_mut = BattleMut::IncurStatus(index, index, status.clone(), *value, *duration);
+                            let monster: &mut Monster = col.get_mut(self.enemies[index]);
+                            Self::mutate(&battle_mut, monster, tick);
+                            currect_tick.push(BattleEvent::Monster(BattleAtom::Mutation(battle_mut)));
+                        }
+                        PhaseShift::Summon(kind, level, count) => {
+                            for _ in 0..*count {
+                                if !self.can_summon() {
+                                    break;
+                                }
+                                self.summon(col, kind, *level);
+                                currect_tick.push(BattleEvent::Monster(BattleAtom::Summon(index, kind.clone(), *level)));
+                            }
+                        }
+                        PhaseShift::Style(style) => {
+                            let monster: &mut Monster = col.get_mut(self.enemies[index]);
+                            let acceptance = monster.acceptance.val(monster.acceptance.get_highest()).max(1);
+                            monster.acceptance = Acceptance::from_style(style.clone(), acceptance);
+                        }
+                        // Picked up by the boss's abilities from its phase.
+                        PhaseShift::Abilities(_) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    // Every wizard is as much of a threat as the damage they dealt to a monster and the
+    // healing they did in front of all of them.
+    fn raise_threat(&mut self, wizard: usize, caused: &[BattleEvent]) {
+        for event in caused.iter() {
             match event {
-                BattleEvent::Wizard(BattleAtom::CastSpell(caster, spell)) 
-                | BattleEvent::Wizard(BattleAtom::TickEffect(caster, spell, _, _))
-                    if statuses.has(&Status::Stunned) && caster == *affectee => BattleEvent::Wizard(BattleAtom::FizzleSpell(caster, spell)),
-                BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Heal(healer, healee, heal))) if healer == *affectee || healee == *affectee => 
-                    BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Heal(healer, healee, heal - statuses.value(&Status::Shocked)))),
-                BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(damager, damagee, damage, glyph))) if damagee == *affectee =>
-                    BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(damager, damagee, 
-                        damage.saturating_sub(statuses.value(&Status::Barrier(glyph.clone())))
-                            .saturating_sub(statuses.value(&Status::Hardened)),
-                        glyph))),
-                BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(damager, damagee, damage, glyph))) if damager == *affectee =>
-                    BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(damager, damagee, 
-                        damage.saturating_add(statuses.value(&Status::Raging))
-                            .saturating_add(statuses.value(&Status::Fluid))
-                            .saturating_add(statuses.value(&Status::Flying))
-                            .saturating_sub(statuses.value(&Status::Weakened))
-                            .saturating_sub(statuses.value(&Status::Hardened)),
-                        glyph))),
-                BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(damager, damagee, damage, glyph))) if damagee == *affectee =>
-                    BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(damager, damagee, 
-                        damage.saturating_add(statuses.value(&Status::Raging))
-                            .saturating_sub(statuses.value(&Status::Flying)), 
-                        glyph))),
-                _ => event,
+                BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(_, monster, damage, _))) =>
+                    *self.threat.entry(*monster).or_default().entry(wizard).or_insert(0) += *damage as u32,
+                BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Heal(_, _, heal))) => {
+                    for monster in self.active_enemies.iter() {
+                        *self.threat.entry(*monster).or_default().entry(wizard).or_insert(0) += *heal as u32;
+                    }
+                }
+                _ => {}
             }
         }
-        ).collect::<Tick>();
-        
     }
 
-    fn tick_mon_status(affectee: &usize, statuses: &StatusSet, battle: &mut Vec<BattleEvent>, tick: Tick) -> Tick {
-        if let Some(s) = statuses.entry(&Status::Burning) {
-            battle.push(BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(*affectee, *affectee, s, Glyph::Fire))));
+    // Hardened wizards taunt: for as long as it lasts they count as more of a threat than anyone.
+    fn weigh_threat(&self, monster: usize, wizards: &mut [Target]) {
+        let table = self.threat.get(&monster);
+        let highest = table.and_then(|table| table.values().cloned().max()).unwrap_or(0);
+        for wizard in wizards.iter_mut() {
+            wizard.threat = match wizard.statuses.entry(&Status::Hardened) {
+                Some(hardened) => highest + hardened as u32,
+                None => table.and_then(|table| table.get(&wizard.index)).cloned().unwrap_or(0),
+            };
         }
-        if let Some(s) = statuses.entry(&Status::Submerged) {
-            battle.push(BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(*affectee, *affectee, s, Glyph::Water))));
+    }
+
+    // Taunting targets go before anyone else, the rest keep their order.
+    fn draw_taunts(targets: &mut [Target]) {
+        targets.sort_by_key(|target| !target.statuses.is_taunting());
+    }
+
+    // Whether the other side can pick the target out by itself; spells that hit everyone still find it.
+    fn is_seen(target: &Target) -> bool {
+        target.hp > 0 && !target.statuses.is_hidden()
+    }
+
+    // The standing target with the most threat goes first, the rest keep their order.
+    fn focus_threat(targets: &mut Vec<Target>) {
+        let top = targets.iter()
+            .enumerate()
+            .filter(|(_, target)| target.hp > 0 && target.threat > 0)
+            .max_by(|(a_at, a), (b_at, b)| a.threat.cmp(&b.threat).then(b_at.cmp(a_at)))
+            .map(|(at, _)| at);
+        if let Some(at) = top {
+            let target = targets.remove(at);
+            targets.insert(0, target);
         }
-        return tick.into_iter().map(|event| {
-            match event {
-                BattleEvent::Monster(BattleAtom::CastSpell(caster, spell)) 
-                | BattleEvent::Monster(BattleAtom::TickEffect(caster, spell, _, _))
-                    if statuses.has(&Status::Stunned) && caster == *affectee => BattleEvent::Monster(BattleAtom::FizzleSpell(caster, spell)),
-                
-                BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Heal(healer, healee, heal))) if healer == *affectee || healee == *affectee => {
-                    if let Some(s) = statuses.entry(&Status::Shocked) {
-                        return BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Heal(healer, healee, heal - s)));
-                    }
-                    event
+    }
+
+    // A wave comes in on its tick, or early once the field is clear, so the fight isn't won before it shows up.
+    fn call_reinforcements(&mut self, col: &mut Colosseum) -> Tick {
+        let mut arrivals = Vec::new();
+        while let Some(wave) = self.waves.first() {
+            if wave.tick as usize > self.past_ticks.len() && !(self.active_enemies.is_empty() && arrivals.is_empty()) {
+                break;
+            }
+            let wave = self.waves.remove(0);
+            for (kind, level) in wave.monsters.into_iter() {
+                self.summon(col, &kind, level);
+                arrivals.push(BattleEvent::Monster(BattleAtom::Reinforce(kind, level)));
+            }
+        }
+        arrivals
+    }
+
+    fn can_summon(&self) -> bool {
+        self.active_enemies.len() < SUMMON_CAP
+    }
+
+    fn summon(&mut self, col: &mut Colosseum, kind: &MonsterType, level: u8) {
+        let monster = col.insert(Monster::new(kind.name(), kind, level));
+        self.active_enemies.push(self.enemies.len());
+        self.push_enemy(monster);
+    }
+
+    fn cool_down(&mut self) {
+        for cooldowns in self.spell_cooldowns.values_mut() {
+            cooldowns.retain(|_, ticks| {
+                *ticks = ticks.saturating_sub(1);
+                *ticks > 0
+            });
+        }
+    }
+
+    fn start_cooldown(&mut self, wizard: usize, spell: &Spell) {
+        self.spell_cooldowns.entry(wizard).or_default().insert(spell.name.to_string(), spell.cooldown());
+    }
+
+    fn is_cooling_down(&self, wizard: &usize, spell: &Spell) -> bool {
+        self.spell_cooldowns.get(wizard).is_some_and(|cooldowns| cooldowns.contains_key(spell.name))
+    }
+
+    // Silenced casters can only turn their magic on themselves.
+    fn can_cast(statuses: &StatusSet, spell: &Spell) -> bool {
+        !statuses.is_silenced() || *spell.target() == TargetType::MeAlone
+    }
+
+    fn is_channelling(spell: &Spell, step_index: u8) -> bool {
+        spell.step(step_index).is_some_and(|step| step.effect.duration.is_channel())
+    }
+
+    fn initiative_order(wizards: &[Target], monsters: &[Target]) -> Tick {
+        let mut order = wizards.iter()
+            .map(|target| BattleEvent::Wizard(BattleAtom::Initiative(target.index, target.initiative)))
+            .chain(monsters.iter()
+                .map(|target| BattleEvent::Monster(BattleAtom::Initiative(target.index, target.initiative))))
+            .collect::<Tick>();
+        // Stable, so ties keep wizards ahead of monsters and lower indices first.
+        order.sort_by(|a, b| match (a, b) {
+            (BattleEvent::Wizard(BattleAtom::Initiative(_, a)) | BattleEvent::Monster(BattleAtom::Initiative(_, a)),
+             BattleEvent::Wizard(BattleAtom::Initiative(_, b)) | BattleEvent::Monster(BattleAtom::Initiative(_, b))) => b.cmp(a),
+            _ => Ordering::Equal,
+        });
+        order
+    }
+
+    fn is_standing(targets: &[Target], index: &usize) -> bool {
+        targets.iter().any(|target| target.index == *index && target.hp > 0)
+    }
+
+    fn project(resolved: &[BattleEvent], wizards: &mut Vec<Target>, monsters: &mut Vec<Target>) {
+        for event in resolved.iter() {
+            let (targets, battle_mut) = match event {
+                BattleEvent::Wizard(BattleAtom::Mutation(battle_mut)) => (&mut *wizards, battle_mut),
+                BattleEvent::Monster(BattleAtom::Mutation(battle_mut)) => (&mut *monsters, battle_mut),
+                _ => continue,
+            };
+            if let Some(target) = targets.iter_mut().find(|target| target.index == battle_mut.affectee()) {
+                match battle_mut {
+                    BattleMut::Damage(_damager, _damagee, damage, _) => target.hp = target.hp.saturating_sub(*damage as u32),
+                    BattleMut::Heal(_healer, _healee, heal) => target.hp = (target.hp + *heal as u32).min(target.max_hp),
+                    BattleMut::IncurStatus(statuser, _statusee, status, value, duration) => target.statuses.insert(status, *value, *duration, Some(*statuser)),
+                    BattleMut::LoseStatus(_statuser, _statusee, status) => target.statuses.remove(status),
                 }
-                BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(damager, damagee, damage, glyph))) if damagee == *affectee =>
-                    BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(damager, damagee, 
-                        damage.saturating_sub(statuses.value(&Status::Barrier(glyph.clone())))
-                            .saturating_sub(statuses.value(&Status::Hardened)), glyph))),
-                BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(damager, damagee, damage, glyph))) if damager == *affectee =>
-                    BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(damager, damagee,
-                        (damage 
-                            + statuses.value(&Status::Raging) 
-                            + statuses.value(&Status::Fluid) 
-                            + statuses.value(&Status::Flying))
-                            .saturating_sub(statuses.value(&Status::Weakened))
-                        , glyph))),
-                BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(damager, damagee, damage, glyph))) if damagee == *affectee =>
-                    BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(damager, damagee, 
-                        (damage 
-                            + statuses.value(&Status::Raging))
-                            .saturating_sub(statuses.value(&Status::Flying))
-                        , glyph))),
-                _ => event,
             }
         }
+    }
+
+    // A combatant's statuses at work on their own for a tick, and on what it is about to do next tick.
+    // Damage over time is dealt by whoever put the status on, so a kill goes to them.
+    fn tick_status(affectee: &usize, is_wizard: bool, statuses: &StatusSet, battle: &mut Vec<BattleEvent>, tick: Tick) -> Tick {
+        let tagged = |is_wizard: bool, atom: BattleAtom| if is_wizard { BattleEvent::Wizard(atom) } else { BattleEvent::Monster(atom) };
+        for (definition, instance) in statuses.instances() {
+            let source = instance.source.unwrap_or(*affectee);
+            let atom = match &definition.on_tick {
+                OnTick::Damage(glyph) => BattleMut::Damage(source, *affectee, instance.value, glyph.clone()),
+                OnTick::DamageByDuration(glyph) => {
+                    let damage = instance.value.saturating_mul(instance.duration);
+                    if damage == 0 {
+                        continue;
+                    }
+                    BattleMut::Damage(source, *affectee, damage, glyph.clone())
+                }
+                OnTick::Heal => BattleMut::Heal(source, *affectee, instance.value),
+                OnTick::Nothing => continue,
+            };
+            battle.push(tagged(is_wizard, BattleAtom::Mutation(atom)));
+        }
+        return tick.into_iter().map(|event| {
+            let (tag, atom) = match event {
+                BattleEvent::Wizard(atom) => (true, atom),
+                BattleEvent::Monster(atom) => (false, atom),
+                outcome => return outcome,
+            };
+            let own = tag == is_wizard;
+            let atom = match atom {
+                BattleAtom::TickEffect(caster, spell, step, _)
+                    if own && caster == *affectee && Self::is_channelling(&spell, step) && statuses.is_interrupted() => BattleAtom::Interrupted(caster, spell),
+                BattleAtom::CastSpell(caster, spell)
+                | BattleAtom::TickEffect(caster, spell, _, _)
+                    if own && caster == *affectee && statuses.is_incapacitated() => BattleAtom::FizzleSpell(caster, spell),
+                atom => atom,
+            };
+            tagged(tag, atom)
+        }
         ).collect::<Tick>();
     }
 
+    // What a combatant's statuses change about this tick's heals and blows, before they land: those it
+    // takes are tagged with its side, those it deals with the other one.
+    fn modify_mutations(affectee: &usize, is_wizard: bool, statuses: &StatusSet, battle: &mut [BattleEvent]) {
+        for event in battle.iter_mut() {
+            let (own, battle_mut) = match event {
+                BattleEvent::Wizard(BattleAtom::Mutation(battle_mut)) => (is_wizard, battle_mut),
+                BattleEvent::Monster(BattleAtom::Mutation(battle_mut)) => (!is_wizard, battle_mut),
+                _ => continue,
+            };
+            match battle_mut {
+                BattleMut::Heal(healer, healee, heal) if own && (*healer == *affectee || *healee == *affectee) =>
+                    *heal = statuses.healing(*heal),
+                BattleMut::Damage(_damager, damagee, damage, glyph) if own && *damagee == *affectee =>
+                    *damage = statuses.taken(*damage, glyph),
+                BattleMut::Damage(damager, _damagee, damage, glyph) if !own && *damager == *affectee =>
+                    *damage = statuses.dealt(*damage, glyph),
+                _ => {}
+            }
+        }
+    }
+
     fn sort_spell_by_targets(spell: &Spell, targets: &mut Vec<Target>) {
         match spell.priority_types() {
             PriorityTypes::Single(priority_type) => targets.sort_by(Self::compare_spell_targets(&priority_type)),
@@ -358,37 +806,188 @@ impl Battle {
         }
     }    
 
-    fn pick_spell(spells: &Vec<Spell>, allies: &Vec<Target>, enemies: &Vec<Target>) -> Spell {
-        let mut best_spell = spells.first().unwrap();
+    // None when there is nothing to pick from, e.g. a wizard who can't pay for any spell.
+    fn pick_spell(spells: &[Spell], caster: &Target, allies: &[Target], enemies: &[Target]) -> Option<Spell> {
+        let mut best_spell = spells.first()?;
         let mut best_score = 0f32;
         for spell in spells.iter() {
-            let mut score = 0f32;
+            // Small base so the mindset still breaks ties when no priority matches.
+            let mut score = 0.1f32;
             for priority_types in spell.priorities() {
                 let len = priority_types.len() as f32;
-                let (targets, num_of) = match spell.target() {
-                    TargetType::Ally(num) => (allies, *num as f32),
-                    TargetType::MeAlone => (allies, 1f32), // todo
-                    TargetType::Enemy(num) => (enemies, *num as f32),
+                let (targets, reach) = match spell.target() {
+                    TargetType::Ally(num) => (allies, *num as usize),
+                    TargetType::MeAlone => (allies, 1), // todo
+                    TargetType::Enemy(num) | TargetType::RandomEnemy(num) => (enemies, *num as usize),
+                    TargetType::Splash => (enemies, 1),
+                    TargetType::AllAllies => (allies, allies.len()),
+                    TargetType::AllEnemies | TargetType::Everyone => (enemies, enemies.len()),
                 };
+                // Scored on the share of the targets it can reach that meet the priority.
+                let num_of = reach.min(targets.len()).max(1) as f32;
                 for priority_type in priority_types.iter() {
                     let mut times_scored = 0f32;
                     for target in targets.iter() {
                         if Self::target_scores_on_priority(target, priority_type) {
                             score += 1f32 / num_of / len;
                             times_scored += 1f32;
-                            if num_of >= times_scored {
+                            if times_scored >= num_of {
                                 break;
                             }
                         }
                     }
                 }
             }
+            // Monsters would rather have a spell that suits whoever threatens them most.
+            if let Some(threat) = enemies.iter().filter(|target| target.hp > 0 && target.threat > 0).max_by_key(|target| target.threat) {
+                if spell.target().reaches_enemies()
+                    && spell.priorities().iter().flatten().any(|priority_type| Self::target_scores_on_priority(threat, priority_type)) {
+                    score += THREAT_FOCUS;
+                }
+            }
+            score *= Self::mindset_weight(caster, spell);
             if score > best_score {
                 best_score = score;
                 best_spell = spell;
             }
         }
-        best_spell.clone()
+        Some(best_spell.clone())
+    }
+
+    // Wizards who got away alive, as opposed to the ones that fell.
+    pub fn fled_allies(&self) -> Vec<usize> {
+        self.left_battle(|event| match event {
+            BattleEvent::Wizard(BattleAtom::Flee(fled)) => Some(*fled),
+            _ => None,
+        })
+    }
+
+    pub fn fallen_allies(&self) -> Vec<usize> {
+        self.left_battle(|event| match event {
+            BattleEvent::Wizard(BattleAtom::Kill(_killer, killed)) => Some(*killed),
+            _ => None,
+        })
+    }
+
+    // Both a kill and an escape can land on the same tick; whichever was recorded first wins.
+    fn left_battle(&self, wanted: fn(&BattleEvent) -> Option<usize>) -> Vec<usize> {
+        let mut gone = Vec::new();
+        let mut left = Vec::new();
+        for event in self.past_ticks.iter().flatten() {
+            let index = match event {
+                BattleEvent::Wizard(BattleAtom::Flee(index))
+                | BattleEvent::Wizard(BattleAtom::Kill(_, index)) => index,
+                _ => continue,
+            };
+            if gone.contains(index) {
+                continue;
+            }
+            gone.push(*index);
+            if let Some(index) = wanted(event) {
+                left.push(index);
+            }
+        }
+        left
+    }
+
+    // Wounded wizards fall back behind whoever still holds the front, eager ones step up.
+    fn wants_to_change_row(caster: &Target, allies: &[Target]) -> Option<Row> {
+        match caster.row {
+            Row::Front if caster.health == TargetHealth::LowHealth && caster.mindset != MindSet::Aggressive
+                && allies.iter().any(|ally| ally.index != caster.index && ally.row == Row::Front && ally.hp > 0) => Some(Row::Back),
+            Row::Back if caster.mindset == MindSet::Aggressive
+                && (caster.health == TargetHealth::HighHealth || caster.health == TargetHealth::FullHealth) => Some(Row::Front),
+            _ => None,
+        }
+    }
+
+    // Newcomers take their usual row: monsters the one of their kind, wizards the front.
+    fn fill_rows(&mut self, col: &Colosseum) {
+        while self.ally_rows.len() < self.allies.len() {
+            self.ally_rows.push(Row::Front);
+        }
+        while self.enemy_rows.len() < self.enemies.len() {
+            let monster: &Monster = col.get(self.enemies[self.enemy_rows.len()]);
+            self.enemy_rows.push(monster.monster_type.definition().row.clone());
+        }
+    }
+
+    fn place(targets: &mut [Target], rows: &[Row]) {
+        for target in targets.iter_mut() {
+            target.row = rows[target.index].clone();
+        }
+    }
+
+    fn wants_to_flee(caster: &Target) -> bool {
+        caster.mindset == MindSet::Coward && caster.health == TargetHealth::LowHealth
+    }
+
+    // Outrunning the fastest pursuer is what matters; never hopeless, never certain.
+    fn flee_chance(runner: &Target, chasers: &[Target]) -> i32 {
+        let fastest = chasers.iter()
+            .filter(|chaser| chaser.hp > 0)
+            .map(|chaser| chaser.initiative)
+            .max()
+            .unwrap_or(0);
+        (40 + 3 * runner.initiative as i32 - 3 * fastest as i32).clamp(10, 90)
+    }
+
+    fn mindset_weight(caster: &Target, spell: &Spell) -> f32 {
+        let harms = spell.effects().iter().any(|effect| effect.harms());
+        let protects = spell.effects().iter().any(|effect| effect.protects());
+        let summons = spell.effects().iter().any(|effect| effect.summons());
+        let cornered = caster.health == TargetHealth::LowHealth;
+        match (&caster.mindset, spell.target()) {
+            (MindSet::Aggressive, _) if summons => 2f32,
+            (MindSet::Aggressive, target) if target.reaches_enemies() && harms => 2f32,
+            (MindSet::Aggressive, target) if target.reaches_enemies() => 1f32,
+            (MindSet::Aggressive, _) => 0.5f32,
+            (MindSet::Defensive, target) if target.reaches_allies() && !target.reaches_enemies() && protects => 2f32,
+            (MindSet::Defensive, TargetType::MeAlone) if protects => 1.5f32,
+            (MindSet::Defensive, target) if target.reaches_enemies() => 0.75f32,
+            (MindSet::Coward, TargetType::MeAlone) if cornered => 3f32,
+            (MindSet::Coward, TargetType::MeAlone) => 1.5f32,
+            (MindSet::Coward, target) if target.reaches_allies() && !target.reaches_enemies() && protects => 1.25f32,
+            (MindSet::Coward, target) if target.reaches_enemies() && cornered => 0.25f32,
+            _ => 1f32,
+        }
+    }
+
+    fn shift_mindsets(&self, currect_tick: &Tick, col: &mut Colosseum, battle_ticks: &mut Tick) {
+        let fallen_allies = currect_tick.iter()
+            .filter(|event| matches!(event, BattleEvent::Wizard(BattleAtom::Kill(_, _))))
+            .count();
+        let fallen_enemies = currect_tick.iter()
+            .filter(|event| matches!(event, BattleEvent::Monster(BattleAtom::Kill(_, _))))
+            .count();
+        for index in self.active_allies.iter() {
+            let wizard: &mut Wizard = col.get_mut(self.allies[*index]);
+            if let Some(mindset) = Self::shift_mindset(wizard, fallen_allies) {
+                wizard.set_mindset(mindset.clone());
+                battle_ticks.push(BattleEvent::Wizard(BattleAtom::MindShift(*index, mindset)));
+            }
+        }
+        for index in self.active_enemies.iter() {
+            let monster: &mut Monster = col.get_mut(self.enemies[*index]);
+            if let Some(mindset) = Self::shift_mindset(monster, fallen_enemies) {
+                monster.set_mindset(mindset.clone());
+                battle_ticks.push(BattleEvent::Monster(BattleAtom::MindShift(*index, mindset)));
+            }
+        }
+    }
+
+    fn shift_mindset<T>(entity: &T, fallen: usize) -> Option<MindSet> where T: BattleEntity {
+        let shifted = match (entity.get_mindset(), entity.health()) {
+            (MindSet::Aggressive, _) => MindSet::Aggressive,
+            (_, TargetHealth::LowHealth) => MindSet::Coward,
+            (MindSet::Neutral, _) if fallen > 0 => MindSet::Defensive,
+            (mindset, _) => mindset.clone(),
+        };
+        if &shifted == entity.get_mindset() {
+            None
+        } else {
+            Some(shifted)
+        }
     }
 
     fn target_scores_on_priority(target: &Target, priority_type: &PriorityType) -> bool {
@@ -403,409 +1002,204 @@ impl Battle {
         }
     }
     
-    fn compare_spell_targets(priority: &PriorityType) -> fn(&Target, &Target) -> Ordering {
-        match priority {
-            PriorityType::LowHealth => |a: &Target, b: &Target| 
-                match a.health { 
-                    TargetHealth::LowHealth => match b.health { 
-                        TargetHealth::LowHealth => Ordering::Equal,
-                        _ => Ordering::Less 
-                    }
-                    _ => Ordering::Greater
-                }, 
-            PriorityType::HighHealth => |a: &Target, b: &Target| 
-                match a.health {
-                    TargetHealth::HighHealth | TargetHealth::FullHealth => match b.health {
-                        TargetHealth::HighHealth | TargetHealth::FullHealth => Ordering::Equal,
-                        _ => Ordering::Less
-                    },
-                    _ => Ordering::Greater
-                },
-            PriorityType::Squishy => |a: &Target, b: &Target| 
-                match a.squishy {
-                    TargetSqiushy::Squishy => match b.squishy {
-                        TargetSqiushy::Squishy => Ordering::Equal,
-                        _ => Ordering::Less
-                    },
-                    _ => Ordering::Greater
-                },
-            PriorityType::Tanky => |a: &Target, b: &Target|
-                match a.squishy {
-                    TargetSqiushy::Tanky => match b.squishy {
-                        TargetSqiushy::Tanky => Ordering::Equal,
-                        _ => Ordering::Less
-                    },
-                    _ => Ordering::Greater
-                },
-            PriorityType::HasStatus(status) => 
-                match status {
-                    Status::Burning => |a: &Target, b: &Target| {
-                        if a.statuses.has(&Status::Burning) {
-                            if b.statuses.has(&Status::Burning) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Submerged => |a: &Target, b: &Target| {
-                        if a.statuses.has(&Status::Submerged) {
-                            if b.statuses.has(&Status::Submerged) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Stunned => |a: &Target, b: &Target| {
-                        if a.statuses.has(&Status::Stunned) {
-                            if b.statuses.has(&Status::Stunned) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Shocked => |a: &Target, b: &Target| {
-                        if a.statuses.has(&Status::Shocked) {
-                            if b.statuses.has(&Status::Shocked) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Weakened => |a: &Target, b: &Target| {
-                        if a.statuses.has(&Status::Weakened) {
-                            if b.statuses.has(&Status::Weakened) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Raging => |a: &Target, b: &Target| {
-                        if a.statuses.has(&Status::Raging) {
-                            if b.statuses.has(&Status::Raging) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Hardened => |a: &Target, b: &Target| {
-                        if a.statuses.has(&Status::Hardened) {
-                            if b.statuses.has(&Status::Hardened) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Fluid => |a: &Target, b: &Target| {
-                        if a.statuses.has(&Status::Fluid) {
-                            if b.statuses.has(&Status::Fluid) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Flying => |a: &Target, b: &Target| {
-                        if a.statuses.has(&Status::Flying) {
-                            if b.statuses.has(&Status::Flying) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Barrier(glyph) => match glyph {
-                        Glyph::Fire => |a: &Target, b: &Target| {
-                            if a.statuses.has(&Status::Barrier(Glyph::Fire)) {
-                                if b.statuses.has(&Status::Barrier(Glyph::Fire)) {
-                                    Ordering::Equal
-                                } else {
-                                    Ordering::Less
-                                }
-                            } else {
-                                Ordering::Greater
-                            }
-                        },
-                        Glyph::Water => |a: &Target, b: &Target| {
-                            if a.statuses.has(&Status::Barrier(Glyph::Water)) {
-                                if b.statuses.has(&Status::Barrier(Glyph::Water)) {
-                                    Ordering::Equal
-                                } else {
-                                    Ordering::Less
-                                }
-                            } else {
-                                Ordering::Greater
-                            }
-                        },
-                        Glyph::Earth => |a: &Target, b: &Target| {
-                            if a.statuses.has(&Status::Barrier(Glyph::Earth)) {
-                                if b.statuses.has(&Status::Barrier(Glyph::Earth)) {
-                                    Ordering::Equal
-                                } else {
-                                    Ordering::Less
-                                }
-                            } else {
-                                Ordering::Greater
-                            }
-                        },
-                        Glyph::Air => |a: &Target, b: &Target| {
-                            if a.statuses.has(&Status::Barrier(Glyph::Air)) {
-                                if b.statuses.has(&Status::Barrier(Glyph::Air)) {
-                                    Ordering::Equal
-                                } else {
-                                    Ordering::Less
-                                }
-                            } else {
-                                Ordering::Greater
-                            }
-                        },
-                        Glyph::Void => |a: &Target, b: &Target| {
-                            if a.statuses.has(&Status::Barrier(Glyph::Void)) {
-                                if b.statuses.has(&Status::Barrier(Glyph::Void)) {
-                                    Ordering::Equal
-                                } else {
-                                    Ordering::Less
-                                }
-                            } else {
-                                Ordering::Greater
-                            }
-                        },
-                    },
-                },
-            PriorityType::NoStatus(status) => 
-                match status {
-                    Status::Burning => |a: &Target, b: &Target| {
-                        if !a.statuses.has(&Status::Burning) {
-                            if !b.statuses.has(&Status::Burning) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Submerged => |a: &Target, b: &Target| {
-                        if !a.statuses.has(&Status::Submerged) {
-                            if !b.statuses.has(&Status::Submerged) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Stunned => |a: &Target, b: &Target| {
-                        if !a.statuses.has(&Status::Stunned) {
-                            if !b.statuses.has(&Status::Stunned) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Shocked => |a: &Target, b: &Target| {
-                        if !a.statuses.has(&Status::Shocked) {
-                            if !b.statuses.has(&Status::Shocked) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Weakened => |a: &Target, b: &Target| {
-                        if !a.statuses.has(&Status::Weakened) {
-                            if !b.statuses.has(&Status::Weakened) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Raging => |a: &Target, b: &Target| {
-                        if !a.statuses.has(&Status::Raging) {
-                            if !b.statuses.has(&Status::Raging) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Hardened => |a: &Target, b: &Target| {
-                        if !a.statuses.has(&Status::Hardened) {
-                            if !b.statuses.has(&Status::Hardened) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Fluid => |a: &Target, b: &Target| {
-                        if !a.statuses.has(&Status::Fluid) {
-                            if !b.statuses.has(&Status::Fluid) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Flying => |a: &Target, b: &Target| {
-                        if !a.statuses.has(&Status::Flying) {
-                            if !b.statuses.has(&Status::Flying) {
-                                Ordering::Equal
-                            } else {
-                                Ordering::Less
-                            }
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Status::Barrier(glyph) => match glyph {
-                        Glyph::Fire => |a: &Target, b: &Target| {
-                            if !a.statuses.has(&Status::Barrier(Glyph::Fire)) {
-                                if !b.statuses.has(&Status::Barrier(Glyph::Fire)) {
-                                    Ordering::Equal
-                                } else {
-                                    Ordering::Less
-                                }
-                            } else {
-                                Ordering::Greater
-                            }
-                        },
-                        Glyph::Water => |a: &Target, b: &Target| {
-                            if !a.statuses.has(&Status::Barrier(Glyph::Water)) {
-                                if !b.statuses.has(&Status::Barrier(Glyph::Water)) {
-                                    Ordering::Equal
-                                } else {
-                                    Ordering::Less
-                                }
-                            } else {
-                                Ordering::Greater
-                            }
-                        },
-                        Glyph::Earth => |a: &Target, b: &Target| {
-                            if !a.statuses.has(&Status::Barrier(Glyph::Earth)) {
-                                if !b.statuses.has(&Status::Barrier(Glyph::Earth)) {
-                                    Ordering::Equal
-                                } else {
-                                    Ordering::Less
-                                }
-                            } else {
-                                Ordering::Greater
-                            }
-                        },
-                        Glyph::Air => |a: &Target, b: &Target| {
-                            if !a.statuses.has(&Status::Barrier(Glyph::Air)) {
-                                if !b.statuses.has(&Status::Barrier(Glyph::Air)) {
-                                    Ordering::Equal
-                                } else {
-                                    Ordering::Less
-                                }
-                            } else {
-                                Ordering::Greater
-                            }
-                        },
-                        Glyph::Void => |a: &Target, b: &Target| {
-                            if !a.statuses.has(&Status::Barrier(Glyph::Void)) {
-                                if !b.statuses.has(&Status::Barrier(Glyph::Void)) {
-                                    Ordering::Equal
-                                } else {
-                                    Ordering::Less
-                                }
-                            } else {
-                                Ordering::Greater
-                            }
-                        },
-                    },
-                },
+    // Targets meeting the priority go first. Two that don't still compare as Greater, as they always
+    // have, so targets keep being sorted the same way.
+    fn compare_spell_targets(priority: &PriorityType) -> impl Fn(&Target, &Target) -> Ordering + '_ {
+        move |a: &Target, b: &Target| {
+            if Self::target_scores_on_priority(a, priority) {
+                if Self::target_scores_on_priority(b, priority) {
+                    Ordering::Equal
+                } else {
+                    Ordering::Less
+                }
+            } else {
+                Ordering::Greater
+            }
         }
     }
 
 }
 
 impl Battle {
-    pub fn tick_effect<T>(tick: &mut Tick, caster: &T, caster_as_target: &Target, spell: &Spell, effect_index: &u8, progress: &u32, battle_ticks: &mut Tick, ally_targets: &mut Vec<Target>, enemy_targets: &mut Vec<Target>) 
+    pub fn tick_effect<T>(tick: &mut Tick, casting: &Casting<T>, effect_index: u8, progress: u32, field: &mut Battlefield)
         where T : BattleEntity + Idable,
             Colosseum: ColosseumArena<T> {
-        if let Some(effect) = spell.effect(*effect_index) {
-            match spell.target() {
+        let Casting { caster, as_target, spell } = *casting;
+        if let Some(step) = spell.step(effect_index) {
+            match step.target.as_ref().unwrap_or(spell.target()) {
+                TargetType::MeAlone => {
+                    Self::land(casting, step, std::iter::once(as_target), false, 1, field.battle_ticks, Self::rolls(field.variance, field.rng));
+                },
                 TargetType::Ally(num) => {
-                    Self::sort_spell_by_targets(spell, enemy_targets);
-                    for target in ally_targets.iter().take(*num as usize) {
-                        battle_ticks.push(caster.as_event(Self::effect(&caster_as_target, target, &effect, &spell.glyph)));
+                    Self::sort_spell_by_targets(spell, field.allies);
+                    let targets = field.allies.iter().filter(|target| target.hp > 0).take(*num as usize);
+                    Self::land(casting, step, targets, false, 1, field.battle_ticks, Self::rolls(field.variance, field.rng));
+                },
+                TargetType::Enemy(num) => {
+                    Self::sort_spell_by_targets(spell, field.enemies);
+                    Self::focus_threat(field.enemies);
+                    Self::draw_taunts(field.enemies);
+                    if caster.is_melee(spell) {
+                        // Stable, so the priorities still decide within each row.
+                        field.enemies.sort_by_key(|target| target.row != Row::Front);
                     }
+                    let targets = field.enemies.iter().filter(|target| Self::is_seen(target)).take(*num as usize);
+                    Self::land(casting, step, targets, true, 1, field.battle_ticks, Self::rolls(field.variance, field.rng));
                 },
-                TargetType::MeAlone => {
-                    battle_ticks.push(
-                        BattleEvent::Monster(Self::effect(
-                            &caster_as_target, 
-                            &caster_as_target, &effect, &spell.glyph)));
+                TargetType::AllAllies => {
+                    let targets = field.allies.iter().filter(|target| target.hp > 0);
+                    Self::land(casting, step, targets, false, 1, field.battle_ticks, Self::rolls(field.variance, field.rng));
                 },
-                TargetType::Enemy(num) => {
-                    Self::sort_spell_by_targets(spell, enemy_targets);
-                    for target in enemy_targets.iter().take(*num as usize) {
-                        battle_ticks.push(BattleEvent::Wizard(Self::effect(&caster_as_target, target, &effect, &spell.glyph)));
+                TargetType::AllEnemies => {
+                    let targets = field.enemies.iter().filter(|target| target.hp > 0);
+                    Self::land(casting, step, targets, true, 1, field.battle_ticks, Self::rolls(field.variance, field.rng));
+                },
+                TargetType::Everyone => {
+                    let targets = field.allies.iter().filter(|target| target.hp > 0);
+                    Self::land(casting, step, targets, false, 1, field.battle_ticks, Self::rolls(field.variance, field.rng));
+                    let targets = field.enemies.iter().filter(|target| target.hp > 0);
+                    Self::land(casting, step, targets, true, 1, field.battle_ticks, Self::rolls(field.variance, field.rng));
+                },
+                TargetType::RandomEnemy(num) => {
+                    let standing = field.enemies.iter().filter(|target| Self::is_seen(target)).collect::<Vec<&Target>>();
+                    let targets = standing.choose_multiple(field.rng, *num as usize).cloned();
+                    Self::land(casting, step, targets, true, 1, field.battle_ticks, Self::rolls(field.variance, field.rng));
+                },
+                TargetType::Splash => {
+                    Self::sort_spell_by_targets(spell, field.enemies);
+                    Self::focus_threat(field.enemies);
+                    Self::draw_taunts(field.enemies);
+                    if let Some(primary) = field.enemies.iter().find(|target| Self::is_seen(target)) {
+                        Self::land(casting, step, std::iter::once(primary), true, 1, field.battle_ticks, Self::rolls(field.variance, field.rng));
+                        let neighbours = field.enemies.iter()
+                            .filter(|target| target.hp > 0 && (target.index + 1 == primary.index || primary.index + 1 == target.index));
+                        Self::land(casting, step, neighbours, true, SPLASH_FALLOFF, field.battle_ticks, Self::rolls(field.variance, field.rng));
                     }
                 },
             };
             
             
-            if effect.done(*progress as u16 + 1) {
-                if let Some(_) = spell.effect(*effect_index + 1) {
-                    tick.push(caster.as_event(BattleAtom::TickEffect(caster_as_target.index, spell.clone(), *effect_index + 1, 0)));
+            if step.effect.done(progress as u16 + 1) {
+                if spell.step(effect_index + 1).is_some() {
+                    tick.push(caster.as_event(BattleAtom::TickEffect(as_target.index, spell.clone(), effect_index + 1, 0)));
                 } else {
-                    tick.push(caster.as_event(BattleAtom::SpellEnd(caster_as_target.index, spell.clone())));
+                    tick.push(caster.as_event(BattleAtom::SpellEnd(as_target.index, spell.clone())));
                 }
             } else {
-                tick.push(caster.as_event(BattleAtom::TickEffect(caster_as_target.index, spell.clone(), *effect_index, *progress + 1)));
+                tick.push(caster.as_event(BattleAtom::TickEffect(as_target.index, spell.clone(), effect_index, progress + 1)));
             }
         }
     }
+
+    fn rolls(variance: bool, rng: &mut GameRng) -> Option<&mut GameRng> {
+        if variance {
+            Some(rng)
+        } else {
+            None
+        }
+    }
+
+    // Lands the step on each target, `falloff` dividing the effect's value (for splash damage).
+    fn land<'a, T>(casting: &Casting<T>, step: &EffectStep, targets: impl Iterator<Item = &'a Target>, at_enemies: bool, falloff: u16, battle_ticks: &mut Tick, mut rolls: Option<&mut GameRng>)
+        where T : BattleEntity {
+        for target in targets {
+            if let Some(effect) = Self::step_effect(step, target) {
+                let effect = Effect { value: effect.value / falloff, ..effect.clone() };
+                let atoms = Self::effect(casting.as_target, target, &effect, &casting.spell.glyph, rolls.as_deref_mut());
+                battle_ticks.extend(atoms.into_iter().map(|atom| if at_enemies { casting.caster.as_enemy_event(atom) } else { casting.caster.as_event(atom) }));
+            }
+        }
+    }
+
+    // Which of the step's effects, if any, lands on the target.
+    fn step_effect<'a>(step: &'a EffectStep, target: &Target) -> Option<&'a Effect> {
+        match &step.condition {
+            Some(condition) if !Self::target_scores_on_priority(target, condition) => step.otherwise.as_ref(),
+            _ => Some(&step.effect),
+        }
+    }
     
 
-    fn effect(caster: &Target, target: &Target, effect: &Effect, glyph: &(Glyph, u16),) -> BattleAtom {
+    // The effect itself, then any reaction it sets off on the target. With `rolls`, a
+    // harmful effect can be dodged and damage is rolled, always drawing in that order.
+    // A status the target is immune to does nothing at all, so there is nothing to roll for.
+    fn effect(caster: &Target, target: &Target, effect: &Effect, glyph: &(Glyph, u16), rolls: Option<&mut GameRng>) -> Vec<BattleAtom> {
+        if let EffectApplication::Status(status, _) = &effect.application {
+            if target.wards.is_immune(status, &glyph.0) {
+                return vec![BattleAtom::Immune(caster.index, target.index, status.clone())];
+            }
+        }
+        let times = match effect.application {
+            EffectApplication::Summon(_, _) => effect.value.max(1),
+            _ => 1,
+        };
+        let mut atoms = (0..times).map(|_| Self::apply(caster, target, effect, glyph)).collect::<Vec<BattleAtom>>();
+        if let Some(rng) = rolls {
+            if effect.harms() && rng.gen_range(0, 100) < Self::dodge_chance(target) {
+                return vec![BattleAtom::Miss(caster.index, target.index)];
+            }
+            atoms = atoms.into_iter().flat_map(|atom| Self::roll(caster, target, atom, glyph, rng)).collect();
+        }
+        if effect.harms() {
+            if let Some(reaction) = Reaction::between(&glyph.0, &target.statuses) {
+                atoms.push(BattleAtom::Reaction(caster.index, target.index, reaction.clone()));
+                atoms.extend(Self::react(caster, target, &reaction, glyph));
+            }
+        }
+        atoms
+    }
+
+    fn roll(caster: &Target, target: &Target, atom: BattleAtom, glyph: &(Glyph, u16), rng: &mut GameRng) -> Vec<BattleAtom> {
+        match atom {
+            BattleAtom::Mutation(BattleMut::Damage(damager, damagee, damage, glyph_hit)) => {
+                let damage = (damage as u32 * rng.gen_range(DAMAGE_ROLL.0, DAMAGE_ROLL.1) as u32 / 100).max(1) as u16;
+                if rng.gen_range(0, 100) < Self::crit_chance(caster, glyph) {
+                    vec![BattleAtom::Crit(caster.index, target.index),
+                        BattleAtom::Mutation(BattleMut::Damage(damager, damagee, damage.saturating_mul(CRIT_MULTIPLIER), glyph_hit))]
+                } else {
+                    vec![BattleAtom::Mutation(BattleMut::Damage(damager, damagee, damage, glyph_hit))]
+                }
+            }
+            atom => vec![atom],
+        }
+    }
+
+    fn crit_chance(caster: &Target, glyph: &(Glyph, u16)) -> i32 {
+        let affinity = (caster.augment.val16(&glyph.0) + caster.augment_cast.val16(&glyph.0)) as i32;
+        (CRIT_CHANCE + CRIT_PER_AFFINITY * affinity).min(MAX_CHANCE)
+    }
+
+    fn dodge_chance(target: &Target) -> i32 {
+        let evasion = target.statuses.quickness() as i32;
+        (DODGE_PER_POINT * evasion).min(MAX_CHANCE)
+    }
+
+    fn react(caster: &Target, target: &Target, reaction: &Reaction, glyph: &(Glyph, u16)) -> Vec<BattleAtom> {
+        let mut atoms: Vec<BattleAtom> = reaction.consumes(&target.statuses).into_iter()
+            .map(|status| BattleAtom::Mutation(BattleMut::LoseStatus(caster.index, target.index, status)))
+            .collect();
+        match reaction {
+            Reaction::Steam => {
+                let burst = target.statuses.value(&Status::Burning).saturating_mul(glyph.1).max(1);
+                atoms.push(BattleAtom::Mutation(BattleMut::Damage(caster.index, target.index, burst, Glyph::Water)));
+            }
+            Reaction::Stagger => 
+                atoms.push(BattleAtom::Mutation(BattleMut::IncurStatus(caster.index, target.index, Status::Stunned, glyph.1, 1))),
+            Reaction::Shatter => {
+                let shards = target.statuses.value(&Status::Frozen).saturating_mul(glyph.1).max(1);
+                atoms.push(BattleAtom::Mutation(BattleMut::Damage(caster.index, target.index, shards, Glyph::Earth)));
+            }
+            Reaction::Evaporate | Reaction::Unravel => {}
+        }
+        atoms
+    }
+
+    fn apply(caster: &Target, target: &Target, effect: &Effect, glyph: &(Glyph, u16),) -> BattleAtom {
         match &effect.application {
             EffectApplication::Damage => {
                     let damage = effect.value + (caster.augment.val16(&glyph.0) + caster.augment_cast.val16(&glyph.0)) * glyph.1;
                     let resist = target.resist.val16(&glyph.0) * glyph.1;
-                    if resist > damage + 1 {
+                    if resist > damage {
                         BattleAtom::Mutation(BattleMut::Damage(caster.index, target.index, 1, glyph.0.clone()))
                     } else {
                         BattleAtom::Mutation(BattleMut::Damage(caster.index, target.index, damage - resist, glyph.0.clone()))
@@ -814,62 +1208,114 @@ impl Battle {
             EffectApplication::Heal => 
                 BattleAtom::Mutation(BattleMut::Heal(caster.index, target.index, effect.value + (caster.augment.val16(&glyph.0) + caster.augment_cast.val16(&glyph.0) + target.augment.val16(&glyph.0)) * glyph.1)),
             EffectApplication::Status(status, duration) => {
+                let duration = &target.wards.duration(status, &glyph.0, *duration);
                 let value = effect.value 
                     + (caster.augment.val16(&glyph.0) + caster.augment_cast.val16(&glyph.0)) * glyph.1;
                 let resist = target.resist.val16(&glyph.0) * glyph.1;
-                if resist > value + 1 {
+                if resist > value {
                     BattleAtom::Mutation(BattleMut::IncurStatus(caster.index, target.index, status.clone(), 1, *duration))
                 } else {
                     BattleAtom::Mutation(BattleMut::IncurStatus(caster.index, target.index, status.clone(), value, *duration))
                 }
             }
-            EffectApplication::RemoveStatus(status) => BattleAtom::Mutation(BattleMut::LoseStatus(caster.index, target.index, status.clone()))
+            EffectApplication::RemoveStatus(status) => BattleAtom::Mutation(BattleMut::LoseStatus(caster.index, target.index, status.clone())),
+            EffectApplication::Summon(kind, level) => BattleAtom::Summon(caster.index, kind.clone(), *level),
         }
     }
 
     pub fn mutate<T>(battle_mut: &BattleMut, target: &mut T, tick: &mut Tick) where T : BattleEntity {
-        match battle_mut {
-            BattleMut::Damage(damager, damagee, damage, _) => {
-                target.set_hp(target.get_hp().saturating_sub(*damage as u32));
-                if target.get_hp() == 0 {
-                    tick.push(target.as_event(BattleAtom::Kill(*damager, *damagee)));
-                }
-            }
-            BattleMut::Heal(_healer, _healee, heal) => {
-                target.set_hp((target.get_hp() + *heal as u32).min(target.get_max_hp()));
-            }
-            BattleMut::IncurStatus(_statuser, _statusee, status, value, duration) => {
-                target.get_status_mut().insert(status, *value, *duration);
-            }
-            BattleMut::LoseStatus(_statuser, _statusee, status) => {
-                target.get_status_mut().remove(status);
+        if let BattleMut::Damage(damager, damagee, _, _) = battle_mut {
+            if apply_mutation(battle_mut, target) {
+                tick.push(target.as_event(BattleAtom::Kill(*damager, *damagee)));
             }
+        } else {
+            apply_mutation(battle_mut, target);
         }
     }
     
 }
 
+// Whatever a mutation can land on, in a battle or in the replay of one.
+pub trait Affectee {
+    fn hp(&self) -> u32;
+    fn set_hp(&mut self, hp: u32);
+    fn max_hp(&self) -> u32;
+    fn status_mut(&mut self) -> &mut StatusSet;
+}
+
+impl<T> Affectee for T where T: BattleEntity {
+    fn hp(&self) -> u32 { self.get_hp() }
+    fn set_hp(&mut self, hp: u32) { BattleEntity::set_hp(self, hp) }
+    fn max_hp(&self) -> u32 { self.get_max_hp() }
+    fn status_mut(&mut self) -> &mut StatusSet { self.get_status_mut() }
+}
+
+// Returns whether it was the blow that brought the target down; only that one is
+// the kill, however many land on it the same tick.
+pub fn apply_mutation<T>(battle_mut: &BattleMut, target: &mut T) -> bool where T: Affectee {
+    match battle_mut {
+        BattleMut::Damage(_damager, _damagee, damage, _) => {
+            let standing = target.hp() > 0;
+            target.set_hp(target.hp().saturating_sub(*damage as u32));
+            return standing && target.hp() == 0;
+        }
+        BattleMut::Heal(_healer, _healee, heal) => {
+            target.set_hp((target.hp() + *heal as u32).min(target.max_hp()));
+        }
+        BattleMut::IncurStatus(statuser, _statusee, status, value, duration) => {
+            target.status_mut().insert(status, *value, *duration, Some(*statuser));
+        }
+        BattleMut::LoseStatus(_statuser, _statusee, status) => {
+            target.status_mut().remove(status);
+        }
+    }
+    false
+}
+
 pub trait BattleEntity {
+    fn get_name(&self) -> &str;
     fn get_hp(&self) -> u32;
     fn set_hp(&mut self, hp: u32);
     fn get_max_hp(&self) -> u32;
+    fn get_speed(&self) -> u16;
+    fn get_mindset(&self) -> &MindSet;
+    fn set_mindset(&mut self, mindset: MindSet);
     fn get_status(&self) -> &StatusSet;
     fn get_status_mut(&mut self) -> &mut StatusSet;
     fn get_affinity(&self) -> &Affinity;
     fn augment(&self) -> &Affinity;
     fn augment_cast(&self) -> Affinity;
     fn resist(&self) -> &Affinity;
+    fn wards(&self) -> Wards;
     fn as_event(&self, atom: BattleAtom) -> BattleEvent;
     fn as_enemy_event(&self, atom: BattleAtom) -> BattleEvent;
 
-    fn as_target(&self, index: usize) -> Target {
-        let health = match self.get_hp() * 100u32 / self.get_max_hp() {
+    // Melee has to get through the enemy's front row before it reaches the back.
+    fn is_melee(&self, _spell: &Spell) -> bool {
+        false
+    }
+
+    fn initiative(&self) -> u16 {
+        let status = self.get_status();
+        if status.is_incapacitated() {
+            return 0;
+        }
+        self.get_speed()
+            .saturating_add(self.augment().val16(&Glyph::Air))
+            .saturating_add(status.quickness())
+    }
+
+    fn health(&self) -> TargetHealth {
+        match self.get_hp() * 100u32 / self.get_max_hp() {
             0 => TargetHealth::NoHealth,
             1..=25  => TargetHealth::LowHealth,
             26..=70 => TargetHealth::MediumHealth,
             71..=99 => TargetHealth::HighHealth,
             _ => TargetHealth::FullHealth,
-        };
+        }
+    }
+
+    fn as_target(&self, index: usize) -> Target {
         let squishy = match self.get_max_hp() {
             0..=50 => TargetSqiushy::Squishy,
             51..=100 => TargetSqiushy::MidRange,
@@ -877,26 +1323,47 @@ pub trait BattleEntity {
         };
         Target {
             index,
-            health,
+            hp: self.get_hp(),
+            max_hp: self.get_max_hp(),
+            initiative: self.initiative(),
+            mindset: self.get_mindset().clone(),
+            health: self.health(),
             squishy,
             statuses: self.get_status().clone(),
+            row: Row::Front,
+            threat: 0,
+            wards: self.wards(),
             augment: self.augment().clone(),
             augment_cast: self.augment_cast().clone(),
             resist: self.resist().clone(),
         }
     }
+
+    fn as_combatant(&self) -> Combatant {
+        Combatant {
+            name: self.get_name().to_string(),
+            hp: self.get_hp(),
+            max_hp: self.get_max_hp(),
+            status: self.get_status().clone(),
+        }
+    }
 }
 
 impl BattleEntity for Wizard {
+    fn get_name(&self) -> &str { &self.name }
     fn get_hp(&self) -> u32 { self.hp }
     fn set_hp(&mut self, hp: u32) { self.hp = hp; }
     fn get_max_hp(&self) -> u32 { self.max_hp }
+    fn get_speed(&self) -> u16 { self.speed }
+    fn get_mindset(&self) -> &MindSet { &self.state }
+    fn set_mindset(&mut self, mindset: MindSet) { self.state = mindset; }
     fn get_status(&self) -> &StatusSet { &self.status }
     fn get_status_mut(&mut self) -> &mut StatusSet { &mut self.status }
     fn get_affinity(&self) -> &Affinity { &self.affinity }
     fn augment(&self) -> &Affinity { &self.affinity }
     fn augment_cast(&self) -> Affinity { self.spellbook_affinity().clone() }
     fn resist(&self) -> &Affinity { &self.affinity }
+    fn wards(&self) -> Wards { self.worn_wards() }
     fn as_event(&self, atom: BattleAtom) -> BattleEvent {
         BattleEvent::Wizard(atom)
     }
@@ -906,21 +1373,29 @@ impl BattleEntity for Wizard {
 }
 
 impl BattleEntity for Monster {
+    fn get_name(&self) -> &str { &self.name }
     fn get_hp(&self) -> u32 { self.hp }
     fn set_hp(&mut self, hp: u32) { self.hp = hp; }
     fn get_max_hp(&self) -> u32 { self.max_hp }
+    fn get_speed(&self) -> u16 { self.speed }
+    fn get_mindset(&self) -> &MindSet { &self.state }
+    fn set_mindset(&mut self, mindset: MindSet) { self.state = mindset; }
     fn get_status(&self) -> &StatusSet { &self.status }
     fn get_status_mut(&mut self) -> &mut StatusSet { &mut self.status }
     fn get_affinity(&self) -> &Affinity { &self.affinity }
     fn augment(&self) -> &Affinity { &self.affinity }
     fn augment_cast(&self) -> Affinity { Affinity::new() }
     fn resist(&self) -> &Affinity { &self.affinity }
+    fn wards(&self) -> Wards { self.monster_type.definition().wards.clone() }
     fn as_event(&self, atom: BattleAtom) -> BattleEvent {
         BattleEvent::Monster(atom)
     }
     fn as_enemy_event(&self, atom: BattleAtom) -> BattleEvent {
         BattleEvent::Wizard(atom)
     }
+    fn is_melee(&self, spell: &Spell) -> bool {
+        self.monster_type.definition().is_melee(spell)
+    }
 }
 
 impl Colosseum {
@@ -928,4 +1403,566 @@ impl Colosseum {
         let target = (self as &dyn ColosseumArena<T>).get(id);
         target.as_target(index)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a::c::e::spell::{spells, EffectDuration};
+    use crate::a::c::e::spell_registry::SpellRegistry;
+
+    fn wizard_damage(battle: &Battle, wizard: usize) -> Vec<u16> {
+        battle.past_ticks.iter().flatten()
+            .filter_map(|event| match event {
+                BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(_, damagee, damage, _))) if *damagee == wizard => Some(*damage),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // A wizard with nothing to cast standing in front of a single troll for a few ticks.
+    fn troll_hits(wizard: Wizard, troll: Monster) -> Vec<u16> {
+        let mut col = Colosseum::headless(1);
+        let wizard = col.insert(wizard);
+        let troll = col.insert(troll);
+        let mut battle = Battle::new(vec![wizard], vec![troll], 1).with_tick_limit(8);
+        battle.run(&mut col);
+        wizard_damage(&battle, 0)
+    }
+
+    #[test]
+    fn statuses_on_the_target_soften_the_blows() {
+        let exposed = troll_hits(Wizard::new("Bob".to_string()), Monster::new("Troll", &MonsterType::Troll, 1));
+        assert!(exposed.iter().any(|damage| *damage >= 5), "{:?}", exposed);
+
+        let mut guarded = Wizard::new("Bob".to_string());
+        guarded.status.insert(&Status::Hardened, 2, 100, None);
+        guarded.status.insert(&Status::Barrier(Glyph::Earth), 1, 100, None);
+        let guarded = troll_hits(guarded, Monster::new("Troll", &MonsterType::Troll, 1));
+        assert_eq!(guarded.len(), exposed.len());
+        for (guarded, exposed) in guarded.iter().zip(exposed.iter()) {
+            assert_eq!(*guarded, exposed.saturating_sub(3));
+        }
+    }
+
+    #[test]
+    fn statuses_on_the_attacker_weaken_the_blows() {
+        let exposed = troll_hits(Wizard::new("Bob".to_string()), Monster::new("Troll", &MonsterType::Troll, 1));
+        let mut weakened = Monster::new("Troll", &MonsterType::Troll, 1);
+        weakened.status.insert(&Status::Weakened, 2, 100, Some(0));
+        let weakened = troll_hits(Wizard::new("Bob".to_string()), weakened);
+        assert_eq!(weakened.len(), exposed.len());
+        for (weakened, exposed) in weakened.iter().zip(exposed.iter()) {
+            assert_eq!(*weakened, exposed.saturating_sub(2));
+        }
+    }
+
+    #[test]
+    fn burning_stops_once_it_runs_out() {
+        let mut col = Colosseum::headless(1);
+        let wizard = col.insert(Wizard::new("Bob".to_string()));
+        let mut slime = Monster::new("Slime", &MonsterType::Slime, 5);
+        slime.status.insert(&Status::Burning, 2, 3, Some(0));
+        let slime = col.insert(slime);
+        let mut battle = Battle::new(vec![wizard], vec![slime], 1).with_tick_limit(10);
+        battle.run(&mut col);
+        let burns = battle.past_ticks.iter().flatten()
+            .filter(|event| matches!(event, BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(0, 0, 2, Glyph::Fire)))))
+            .count();
+        assert_eq!(burns, 3);
+    }
+
+    #[test]
+    fn the_same_seed_fights_the_same_battle() {
+        let fight = |seed| {
+            let mut col = Colosseum::headless(seed);
+            let mut wizard = Wizard::new("Bob".to_string());
+            wizard.add_spell_to_book(spells::by_name("fireball").unwrap());
+            let wizard = col.insert(wizard);
+            let orc = col.insert(Monster::new("Orc", &MonsterType::Orc, 2));
+            let mut battle = Battle::new(vec![wizard], vec![orc], seed);
+            battle.run(&mut col);
+            battle.past_ticks
+        };
+        assert_eq!(fight(11), fight(11));
+    }
+
+    #[test]
+    fn a_wizard_without_mana_lets_the_turn_pass() {
+        let mut col = Colosseum::headless(1);
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.add_spell_to_book(spells::by_name("fireball").unwrap());
+        wizard.mana = 0;
+        let wizard = col.insert(wizard);
+        let slime = col.insert(Monster::new("Slime", &MonsterType::Slime, 5));
+        let mut battle = Battle::new(vec![wizard], vec![slime], 1).with_tick_limit(3);
+        battle.run(&mut col);
+        let events = battle.past_ticks.iter().flatten().collect::<Vec<&BattleEvent>>();
+        assert!(events.contains(&&BattleEvent::Wizard(BattleAtom::OutOfMana(0))), "{:?}", events);
+        assert_eq!(ColosseumArena::<Wizard>::get(&col, wizard).mana, MANA_REGEN);
+    }
+
+    #[test]
+    fn a_dragon_does_not_burn() {
+        let mut col = Colosseum::headless(2);
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.add_spell_to_book(spells::by_name("fireball").unwrap());
+        let wizard = col.insert(wizard);
+        let dragon = col.insert(Monster::new("Smaug", &MonsterType::Dragon, 1));
+        let mut battle = Battle::new(vec![wizard], vec![dragon], 2).with_tick_limit(6);
+        battle.run(&mut col);
+        let events = battle.past_ticks.iter().flatten().collect::<Vec<&BattleEvent>>();
+        assert!(events.contains(&&BattleEvent::Monster(BattleAtom::Immune(0, 0, Status::Burning))), "{:?}", events);
+        assert!(!events.iter().any(|event| matches!(event, BattleEvent::Monster(BattleAtom::Mutation(BattleMut::IncurStatus(..))))));
+    }
+
+    #[test]
+    fn a_crowded_side_gets_no_answer() {
+        let mut col = Colosseum::headless(4);
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.max_hp = 10_000;
+        wizard.hp = 10_000;
+        let wizard = col.insert(wizard);
+        let void_lord = col.insert(Monster::new("Vex", &MonsterType::VoidLord, 3));
+        let mut battle = Battle::new(vec![wizard], vec![void_lord], 4).with_tick_limit(100);
+        battle.run(&mut col);
+        let summons = battle.past_ticks.iter().flatten()
+            .filter(|event| matches!(event, BattleEvent::Monster(BattleAtom::Summon(..))))
+            .count();
+        assert_eq!(summons, SUMMON_CAP - 1);
+        assert_eq!(battle.enemies.len(), SUMMON_CAP);
+    }
+
+    #[test]
+    fn mutations_land_on_their_own_side() {
+        let mut col = Colosseum::headless(5);
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.add_spell_to_book(spells::by_name("fireball").unwrap());
+        let wizard = col.insert(wizard);
+        let slime = col.insert(Monster::new("Slime", &MonsterType::Slime, 5));
+        let mut battle = Battle::new(vec![wizard], vec![slime], 5).with_tick_limit(6);
+        battle.run(&mut col);
+        let taken = |side: fn(&BattleEvent) -> Option<&BattleAtom>| battle.past_ticks.iter().flatten()
+            .filter_map(side)
+            .map(|atom| match atom {
+                BattleAtom::Mutation(BattleMut::Damage(_, _, damage, _)) => *damage as u32,
+                _ => 0,
+            })
+            .sum::<u32>();
+        let by_wizards = taken(|event| match event { BattleEvent::Monster(atom) => Some(atom), _ => None });
+        let by_monsters = taken(|event| match event { BattleEvent::Wizard(atom) => Some(atom), _ => None });
+        assert!(by_wizards > 0 && by_monsters > 0);
+        let slime: &Monster = col.get(slime);
+        assert_eq!(slime.max_hp - slime.hp, by_wizards);
+        let wizard: &Wizard = col.get(wizard);
+        assert_eq!(wizard.max_hp - wizard.hp, by_monsters);
+    }
+
+    fn turn_order(wizard: Wizard, monster: Monster) -> Vec<BattleEvent> {
+        let mut col = Colosseum::headless(6);
+        let wizard = col.insert(wizard);
+        let monster = col.insert(monster);
+        let mut battle = Battle::new(vec![wizard], vec![monster], 6).with_tick_limit(3);
+        battle.run(&mut col);
+        battle.past_ticks[0].iter()
+            .filter(|event| matches!(event, BattleEvent::Wizard(BattleAtom::Initiative(..)) | BattleEvent::Monster(BattleAtom::Initiative(..))))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn the_quicker_combatant_takes_the_first_turn() {
+        let hellcat = || Monster::new("Hellcat", &MonsterType::Hellcat, 1);
+        let walking = turn_order(Wizard::new("Bob".to_string()), hellcat());
+        assert!(matches!(walking[..], [BattleEvent::Monster(BattleAtom::Initiative(0, _)), BattleEvent::Wizard(BattleAtom::Initiative(0, _))]), "{:?}", walking);
+
+        let mut flying = Wizard::new("Bob".to_string());
+        flying.status.insert(&Status::Flying, 10, 3, None);
+        let flying = turn_order(flying, hellcat());
+        assert!(matches!(flying[..], [BattleEvent::Wizard(BattleAtom::Initiative(0, _)), BattleEvent::Monster(BattleAtom::Initiative(0, _))]), "{:?}", flying);
+
+        let mut stunned = Wizard::new("Bob".to_string());
+        stunned.status.insert(&Status::Stunned, 1, 3, None);
+        let stunned = turn_order(stunned, Monster::new("Slime", &MonsterType::Slime, 1));
+        assert!(matches!(stunned[..], [BattleEvent::Monster(BattleAtom::Initiative(0, _)), BattleEvent::Wizard(BattleAtom::Initiative(0, 0))]), "{:?}", stunned);
+    }
+
+    fn pick_in_mindset(mindset: MindSet) -> &'static str {
+        let spells = vec![spells::by_name("fireball").unwrap(), spells::by_name("Flame wall").unwrap()];
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.state = mindset;
+        let caster = wizard.as_target(0);
+        let goblin = Monster::new("Goblin", &MonsterType::Goblin, 1).as_target(0);
+        Battle::pick_spell(&spells, &caster, &[caster.clone()], &[goblin]).unwrap().name
+    }
+
+    #[test]
+    fn the_mindset_picks_the_spell() {
+        assert_eq!(pick_in_mindset(MindSet::Aggressive), "fireball");
+        assert_eq!(pick_in_mindset(MindSet::Defensive), "Flame wall");
+    }
+
+    #[test]
+    fn the_wounded_lose_their_nerve() {
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.state = MindSet::Neutral;
+        assert_eq!(Battle::shift_mindset(&wizard, 0), None);
+        assert_eq!(Battle::shift_mindset(&wizard, 1), Some(MindSet::Defensive));
+        wizard.hp = 10;
+        assert_eq!(Battle::shift_mindset(&wizard, 0), Some(MindSet::Coward));
+        wizard.state = MindSet::Aggressive;
+        assert_eq!(Battle::shift_mindset(&wizard, 1), None);
+    }
+
+    #[test]
+    fn a_cornered_coward_gets_away() {
+        let mut col = Colosseum::headless(7);
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.state = MindSet::Coward;
+        wizard.hp = 20;
+        let wizard = col.insert(wizard);
+        let slime = col.insert(Monster::new("Slime", &MonsterType::Slime, 1));
+        let mut battle = Battle::new(vec![wizard], vec![slime], 7).with_tick_limit(20);
+        assert_eq!(battle.run(&mut col), BattleEvent::Retreat);
+        assert_eq!(battle.fled_allies(), vec![0]);
+        assert!(battle.fallen_allies().is_empty());
+    }
+
+    #[test]
+    fn an_else_lands_where_the_if_does_not() {
+        let step = EffectStep::new(Effect::new(3, EffectDuration::Instant, EffectApplication::Damage))
+            .when(PriorityType::HasStatus(Status::Burning))
+            .otherwise(Effect::new(1, EffectDuration::Instant, EffectApplication::Status(Status::Burning, 3)));
+        let mut burning = Monster::new("Slime", &MonsterType::Slime, 1);
+        burning.status.insert(&Status::Burning, 1, 2, None);
+        let dry = Monster::new("Slime", &MonsterType::Slime, 1);
+        assert_eq!(Battle::step_effect(&step, &burning.as_target(0)), Some(&step.effect));
+        assert_eq!(Battle::step_effect(&step, &dry.as_target(1)), step.otherwise.as_ref());
+        assert_eq!(Battle::step_effect(&EffectStep { otherwise: None, ..step.clone() }, &dry.as_target(1)), None);
+    }
+
+    #[test]
+    fn water_on_a_burning_target_steams() {
+        let caster = Wizard::new("Bob".to_string()).as_target(0);
+        let mut slime = Monster::new("Slime", &MonsterType::Slime, 1);
+        slime.status.insert(&Status::Burning, 2, 3, None);
+        let splash = Effect::new(3, EffectDuration::Instant, EffectApplication::Damage);
+
+        let atoms = Battle::effect(&caster, &slime.as_target(0), &splash, &(Glyph::Water, 2), None);
+        assert_eq!(atoms, vec![
+            BattleAtom::Mutation(BattleMut::Damage(0, 0, 3, Glyph::Water)),
+            BattleAtom::Reaction(0, 0, Reaction::Steam),
+            BattleAtom::Mutation(BattleMut::LoseStatus(0, 0, Status::Burning)),
+            BattleAtom::Mutation(BattleMut::Damage(0, 0, 4, Glyph::Water)),
+        ]);
+
+        // Healing doesn't set anything off, and neither does water on a dry target.
+        let heal = Effect::new(3, EffectDuration::Instant, EffectApplication::Heal);
+        assert_eq!(Battle::effect(&caster, &slime.as_target(0), &heal, &(Glyph::Water, 2), None).len(), 1);
+        let dry = Monster::new("Slime", &MonsterType::Slime, 1);
+        assert_eq!(Battle::effect(&caster, &dry.as_target(0), &splash, &(Glyph::Water, 2), None).len(), 1);
+    }
+
+    #[test]
+    fn void_eats_every_barrier() {
+        let caster = Wizard::new("Bob".to_string()).as_target(0);
+        let mut slime = Monster::new("Slime", &MonsterType::Slime, 1);
+        slime.status.insert(&Status::Barrier(Glyph::Fire), 2, 3, None);
+        slime.status.insert(&Status::Barrier(Glyph::Earth), 2, 3, None);
+        let rend = Effect::new(1, EffectDuration::Instant, EffectApplication::Damage);
+        let atoms = Battle::effect(&caster, &slime.as_target(0), &rend, &(Glyph::Void, 1), None);
+        assert!(atoms.contains(&BattleAtom::Reaction(0, 0, Reaction::Unravel)));
+        assert!(atoms.contains(&BattleAtom::Mutation(BattleMut::LoseStatus(0, 0, Status::Barrier(Glyph::Fire)))));
+        assert!(atoms.contains(&BattleAtom::Mutation(BattleMut::LoseStatus(0, 0, Status::Barrier(Glyph::Earth)))));
+    }
+
+    #[test]
+    fn a_spell_rests_before_it_is_cast_again() {
+        let fireball = spells::by_name("fireball").unwrap();
+        let mut battle = Battle::new(Vec::new(), Vec::new(), 1);
+        battle.start_cooldown(0, &fireball);
+        assert!(battle.is_cooling_down(&0, &fireball));
+        assert!(!battle.is_cooling_down(&1, &fireball));
+        for _ in 1..fireball.cooldown() {
+            battle.cool_down();
+            assert!(battle.is_cooling_down(&0, &fireball));
+        }
+        battle.cool_down();
+        assert!(!battle.is_cooling_down(&0, &fireball));
+    }
+
+    #[test]
+    fn a_shock_breaks_a_channel_and_a_stun_everything() {
+        let rage = spells::by_name("Rage").unwrap();
+        let next = || vec![
+            BattleEvent::Wizard(BattleAtom::TickEffect(0, rage.clone(), 1, 1)),
+            BattleEvent::Wizard(BattleAtom::TickEffect(0, rage.clone(), 2, 0)),
+            BattleEvent::Monster(BattleAtom::TickEffect(0, rage.clone(), 1, 1)),
+        ];
+        let mut shocked = StatusSet::new();
+        shocked.insert(&Status::Shocked, 1, 2, None);
+        assert_eq!(Battle::tick_status(&0, true, &shocked, &mut Vec::new(), next()), vec![
+            BattleEvent::Wizard(BattleAtom::Interrupted(0, rage.clone())),
+            BattleEvent::Wizard(BattleAtom::TickEffect(0, rage.clone(), 2, 0)),
+            BattleEvent::Monster(BattleAtom::TickEffect(0, rage.clone(), 1, 1)),
+        ]);
+
+        let mut stunned = StatusSet::new();
+        stunned.insert(&Status::Stunned, 1, 2, None);
+        assert_eq!(Battle::tick_status(&0, true, &stunned, &mut Vec::new(), next()), vec![
+            BattleEvent::Wizard(BattleAtom::Interrupted(0, rage.clone())),
+            BattleEvent::Wizard(BattleAtom::FizzleSpell(0, rage.clone())),
+            BattleEvent::Monster(BattleAtom::TickEffect(0, rage.clone(), 1, 1)),
+        ]);
+    }
+
+    // Where one step of a spell hitting for 4 lands among five slimes, the middle one badly hurt.
+    fn struck(target: &str) -> Vec<(usize, u16)> {
+        let source = format!("spell strike\n glyph Air 1\n style Arcane 1\n priority LowHealth\n target {}\n effect 4 Instant Damage\n", target);
+        let registry = SpellRegistry::parse(&source, "test").unwrap();
+        let spell = registry.get("strike").unwrap();
+        let wizard = Wizard::new("Bob".to_string());
+        let mut allies = vec![wizard.as_target(0)];
+        let mut enemies = (0..5)
+            .map(|index| {
+                let mut slime = Monster::new("Slime", &MonsterType::Slime, 1);
+                if index == 2 {
+                    slime.hp = 1;
+                }
+                slime.as_target(index)
+            })
+            .collect::<Vec<Target>>();
+        let mut battle_ticks = Tick::new();
+        let casting = Casting { caster: &wizard, as_target: &wizard.as_target(0), spell };
+        Battle::tick_effect(&mut Tick::new(), &casting, 1, 0, &mut Battlefield {
+            battle_ticks: &mut battle_ticks,
+            allies: &mut allies,
+            enemies: &mut enemies,
+            rng: &mut GameRng::new(1),
+            variance: false,
+        });
+        let mut struck = battle_ticks.iter()
+            .filter_map(|event| match event {
+                BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(_, damagee, damage, _))) => Some((*damagee, *damage)),
+                _ => None,
+            })
+            .collect::<Vec<(usize, u16)>>();
+        struck.sort();
+        struck
+    }
+
+    #[test]
+    fn area_spells_reach_as_far_as_they_say() {
+        assert_eq!(struck("Enemy 1"), vec![(2, 4)]);
+        assert_eq!(struck("AllEnemies"), vec![(0, 4), (1, 4), (2, 4), (3, 4), (4, 4)]);
+        assert_eq!(struck("Splash"), vec![(1, 2), (2, 4), (3, 2)]);
+        let random = struck("RandomEnemy 3");
+        assert_eq!(random.len(), 3);
+        assert!(random.iter().all(|(_, damage)| *damage == 4));
+        // Everyone includes the caster's own side, which this tally leaves out.
+        assert_eq!(struck("Everyone").len(), 5);
+    }
+
+    #[test]
+    fn melee_cannot_reach_past_the_front_row() {
+        let mut col = Colosseum::headless(8);
+        let front = (0..2).map(|i| col.insert(Wizard::new(format!("Front {}", i)))).collect::<Vec<Index>>();
+        let mut hiding = Wizard::new("Back".to_string());
+        hiding.hp = 20;
+        let hiding = col.insert(hiding);
+        let mut party = Party::new(vec![front[0], front[1], hiding]);
+        party.back_row.push(hiding);
+        let troll = col.insert(Monster::new("Troll", &MonsterType::Troll, 1));
+        let mut battle = Battle::new(party.members.clone(), vec![troll], 8).in_formation(&party).with_tick_limit(6);
+        battle.run(&mut col);
+        assert!(!wizard_damage(&battle, 0).is_empty() || !wizard_damage(&battle, 1).is_empty());
+        assert!(wizard_damage(&battle, 2).is_empty());
+    }
+
+    #[test]
+    fn the_wounded_step_back_and_the_eager_step_up() {
+        let mut wounded = Wizard::new("Bob".to_string());
+        wounded.state = MindSet::Neutral;
+        wounded.hp = 10;
+        let wounded = wounded.as_target(0);
+        let mut holding = Wizard::new("Rob".to_string()).as_target(1);
+        assert_eq!(Battle::wants_to_change_row(&wounded, &vec![wounded.clone(), holding.clone()]), Some(Row::Back));
+        // With no one left to hold the front, there is nowhere to fall back to.
+        holding.row = Row::Back;
+        assert_eq!(Battle::wants_to_change_row(&wounded, &vec![wounded.clone(), holding.clone()]), None);
+
+        let mut eager = Wizard::new("Bob".to_string());
+        eager.state = MindSet::Aggressive;
+        let mut eager = eager.as_target(0);
+        eager.row = Row::Back;
+        assert_eq!(Battle::wants_to_change_row(&eager, &vec![eager.clone()]), Some(Row::Front));
+    }
+
+    #[test]
+    fn monsters_turn_on_whoever_hurts_them_most_unless_taunted() {
+        let mut battle = Battle::new(Vec::new(), Vec::new(), 1);
+        battle.active_enemies = vec![0, 1];
+        battle.raise_threat(1, &[BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(1, 0, 6, Glyph::Fire)))]);
+        battle.raise_threat(2, &[BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(2, 0, 2, Glyph::Fire)))]);
+        battle.raise_threat(2, &[BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Heal(2, 1, 3)))]);
+        assert_eq!(battle.threat[&0][&1], 6);
+        assert_eq!(battle.threat[&0][&2], 5);
+        assert_eq!(battle.threat[&1][&2], 3);
+
+        let mut wizards = (0..3).map(|index| Wizard::new("Bob".to_string()).as_target(index)).collect::<Vec<Target>>();
+        battle.weigh_threat(0, &mut wizards);
+        Battle::focus_threat(&mut wizards);
+        assert_eq!(wizards.iter().map(|wizard| wizard.index).collect::<Vec<usize>>(), vec![1, 0, 2]);
+
+        let mut wizards = (0..3).map(|index| Wizard::new("Bob".to_string()).as_target(index)).collect::<Vec<Target>>();
+        wizards[0].statuses.insert(&Status::Hardened, 2, 2, None);
+        battle.weigh_threat(0, &mut wizards);
+        assert_eq!(wizards[0].threat, 8);
+        Battle::focus_threat(&mut wizards);
+        assert_eq!(wizards[0].index, 0);
+    }
+
+    #[test]
+    fn rolled_blows_crit_miss_and_vary_within_range() {
+        let caster = Wizard::new("Bob".to_string()).as_target(0);
+        let slime = Monster::new("Slime", &MonsterType::Slime, 1);
+        let blow = Effect::new(20, EffectDuration::Instant, EffectApplication::Damage);
+        let glyph = (Glyph::Air, 1);
+        let swing = |rng: &mut GameRng, target: &Target| Battle::effect(&caster, target, &blow, &glyph, Some(rng));
+        let base = match Battle::effect(&caster, &slime.as_target(0), &blow, &glyph, None)[..] {
+            [BattleAtom::Mutation(BattleMut::Damage(_, _, damage, _))] => damage,
+            ref atoms => panic!("{:?}", atoms),
+        };
+        let rolled = (base * DAMAGE_ROLL.0 / 100)..(base * DAMAGE_ROLL.1 / 100 + 1);
+
+        let mut rng = GameRng::new(9);
+        let swings = (0..400).map(|_| swing(&mut rng, &slime.as_target(0))).collect::<Vec<Vec<BattleAtom>>>();
+        let mut replayed = GameRng::new(9);
+        assert!(swings.iter().all(|atoms| *atoms == swing(&mut replayed, &slime.as_target(0))));
+        assert!(swings.iter().any(|atoms| matches!(atoms[0], BattleAtom::Crit(0, 0))));
+        assert!(!swings.iter().any(|atoms| matches!(atoms[0], BattleAtom::Miss(..))));
+        for atoms in swings.iter() {
+            match &atoms[..] {
+                [BattleAtom::Crit(..), BattleAtom::Mutation(BattleMut::Damage(_, _, damage, _))] =>
+                    assert!(rolled.contains(&(damage / CRIT_MULTIPLIER)), "{} from {}", damage, base),
+                [BattleAtom::Mutation(BattleMut::Damage(_, _, damage, _))] => assert!(rolled.contains(damage), "{} from {}", damage, base),
+                atoms => panic!("{:?}", atoms),
+            }
+        }
+
+        let mut flying = Monster::new("Slime", &MonsterType::Slime, 1);
+        flying.status.insert(&Status::Flying, 5, 3, None);
+        let flying = flying.as_target(0);
+        assert_eq!(Battle::dodge_chance(&flying), 15);
+        assert!((0..400).any(|_| swing(&mut rng, &flying) == vec![BattleAtom::Miss(0, 0)]));
+        assert_eq!(Battle::effect(&caster, &flying, &blow, &glyph, None), vec![BattleAtom::Mutation(BattleMut::Damage(0, 0, base, Glyph::Air))]);
+    }
+
+    #[test]
+    fn a_burn_kill_goes_to_whoever_lit_it() {
+        let mut col = Colosseum::headless(1);
+        let wizards = (0..2).map(|i| col.insert(Wizard::new(format!("Bob {}", i)))).collect::<Vec<Index>>();
+        let mut slime = Monster::new("Slime", &MonsterType::Slime, 1);
+        slime.hp = 2;
+        slime.status.insert(&Status::Burning, 2, 3, Some(1));
+        let slime = col.insert(slime);
+        let mut battle = Battle::new(wizards, vec![slime], 1).with_tick_limit(4);
+        assert_eq!(battle.run(&mut col), BattleEvent::Victory);
+        let events = battle.past_ticks.iter().flatten().collect::<Vec<&BattleEvent>>();
+        assert!(events.contains(&&BattleEvent::Monster(BattleAtom::Kill(1, 0))), "{:?}", events);
+    }
+
+    #[test]
+    fn new_statuses_do_what_they_say() {
+        let mut statuses = StatusSet::new();
+        statuses.insert(&Status::Poisoned, 2, 3, Some(4));
+        statuses.insert(&Status::Regenerating, 5, 2, None);
+        let mut battle = Tick::new();
+        Battle::tick_status(&0, true, &statuses, &mut battle, Tick::new());
+        assert_eq!(battle, vec![
+            BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(4, 0, 6, Glyph::Void))),
+            BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Heal(0, 0, 5))),
+        ]);
+
+        let mut silenced = StatusSet::new();
+        silenced.insert(&Status::Silenced, 1, 2, None);
+        assert!(!Battle::can_cast(&silenced, &spells::by_name("fireball").unwrap()));
+        assert!(Battle::can_cast(&silenced, &spells::by_name("Rage").unwrap()));
+
+        let mut hidden = Wizard::new("Bob".to_string());
+        hidden.status.insert(&Status::Invisible, 1, 2, None);
+        assert!(!Battle::is_seen(&hidden.as_target(0)));
+        assert!(Battle::is_seen(&Wizard::new("Rob".to_string()).as_target(1)));
+    }
+
+    fn fireball_against_goblin(tick_limit: u16) -> (BattleEvent, usize) {
+        let mut col = Colosseum::headless(3);
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.add_spell_to_book(spells::by_name("fireball").unwrap());
+        let wizard = col.insert(wizard);
+        let goblin = col.insert(Monster::new("Goblin", &MonsterType::Goblin, 2));
+        let mut battle = Battle::new(vec![wizard], vec![goblin], 3).with_tick_limit(tick_limit);
+        let outcome = battle.run(&mut col);
+        (outcome, battle.past_ticks.len())
+    }
+
+    #[test]
+    fn a_win_on_the_last_tick_is_still_a_win() {
+        let (outcome, ticks) = fireball_against_goblin(TICK_LIMIT);
+        assert_eq!(outcome, BattleEvent::Victory);
+        // The tick the goblin falls on and the outcome after it both fit in the limit.
+        assert_eq!(fireball_against_goblin(ticks as u16), (BattleEvent::Victory, ticks));
+        let (outcome, drawn) = fireball_against_goblin(ticks as u16 - 1);
+        assert_eq!(outcome, BattleEvent::Draw);
+        assert!(drawn < ticks, "{}", drawn);
+    }
+
+    #[test]
+    fn a_fight_where_no_one_gets_hurt_is_called() {
+        let mut col = Colosseum::headless(1);
+        let wizard = col.insert(Wizard::new("Bob".to_string()));
+        let mut troll = Monster::new("Troll", &MonsterType::Troll, 1);
+        troll.status.insert(&Status::Stunned, 1, 100, None);
+        let troll = col.insert(troll);
+        let mut battle = Battle::new(vec![wizard], vec![troll], 1).with_stalemate_ticks(5);
+        assert_eq!(battle.run(&mut col), BattleEvent::Draw);
+        assert!(battle.past_ticks.len() < 10, "{}", battle.past_ticks.len());
+    }
+
+    #[test]
+    fn a_fire_that_only_keeps_up_with_regeneration_is_called() {
+        let mut col = Colosseum::headless(1);
+        let wizard = col.insert(Wizard::new("Bob".to_string()));
+        let mut troll = Monster::new("Troll", &MonsterType::Troll, 1);
+        troll.hp = troll.max_hp / 2;
+        troll.status.insert(&Status::Stunned, 1, 100, None);
+        troll.status.insert(&Status::Burning, 3, 100, Some(0));
+        troll.status.insert(&Status::Regenerating, 5, 100, None);
+        let troll = col.insert(troll);
+        let mut battle = Battle::new(vec![wizard], vec![troll], 1).with_stalemate_ticks(5);
+        assert_eq!(battle.run(&mut col), BattleEvent::Draw);
+        assert!(battle.past_ticks.len() < 10, "{}", battle.past_ticks.len());
+    }
+
+    #[test]
+    fn modifiers_apply_to_the_mutations_of_the_tick() {
+        let mut statuses = StatusSet::new();
+        statuses.insert(&Status::Hardened, 3, 2, None);
+        statuses.insert(&Status::Barrier(Glyph::Fire), 4, 2, None);
+        let mut battle = vec![
+            BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(0, 1, 10, Glyph::Fire))),
+            BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(0, 1, 10, Glyph::Water))),
+            BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(1, 0, 10, Glyph::Water))),
+            BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(1, 0, 10, Glyph::Water))),
+        ];
+        Battle::modify_mutations(&1, true, &statuses, &mut battle);
+        let damage = battle.iter()
+            .map(|event| match event {
+                BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(_, _, damage, _)))
+                | BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(_, _, damage, _))) => *damage,
+                _ => unreachable!(),
+            })
+            .collect::<Vec<u16>>();
+        // Taken through Hardened and the fire Barrier, dealt through Hardened, someone else's untouched.
+        assert_eq!(damage, vec![3, 7, 10, 7]);
+    }
+}
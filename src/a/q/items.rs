@@ -1,9 +1,13 @@
 use crate::a::c::e::{Glyph, Style};
+use crate::a::c::e::status::Wards;
+use crate::a::c::e::wiz::Wizard;
 
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum ItemAbility {
     Augment(Glyph, u8),
     Lean(Style, u8),
+    Restore(u32), // mana
+    Ward(Glyph, u8), // percent of every status the glyph brings on shrugged off, while worn
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -12,6 +16,7 @@ pub struct Item {
     name: &'static str,
     glyph: Glyph,
     ability: ItemAbility,
+    extra: Option<ItemAbility>,
 }
 
 impl Item {
@@ -21,6 +26,47 @@ impl Item {
             name,
             glyph,
             ability,
+            extra: None,
+        }
+    }
+
+    // Gives the item a second ability on top of the one it was made with.
+    pub const fn also(mut self, ability: ItemAbility) -> Self {
+        self.extra = Some(ability);
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn abilities(&self) -> impl Iterator<Item = &ItemAbility> {
+        std::iter::once(&self.ability).chain(self.extra.iter())
+    }
+
+    // Only items that restore something can be drunk; returns whether it was.
+    pub fn drink(&self, wizard: &mut Wizard) -> bool {
+        let mut drunk = false;
+        for ability in self.abilities() {
+            if let ItemAbility::Restore(mana) = ability {
+                wizard.restore_mana(*mana);
+                drunk = true;
+            }
+        }
+        drunk
+    }
+
+    // Only items that ward something off can be worn.
+    pub fn is_wearable(&self) -> bool {
+        self.abilities().any(|ability| matches!(ability, ItemAbility::Ward(..)))
+    }
+
+    // Adds what the item wards off to the wards of whoever wears it.
+    pub fn ward(&self, wards: &mut Wards) {
+        for ability in self.abilities() {
+            if let ItemAbility::Ward(glyph, percent) = ability {
+                wards.ward_glyph(glyph, *percent);
+            }
         }
     }
 }
@@ -76,31 +122,31 @@ pub mod amulets {
         "Amulet of Fire",
         Glyph::Fire,
         ItemAbility::Augment(Glyph::Fire, 1),
-    );
+    ).also(ItemAbility::Ward(Glyph::Fire, 50));
     pub const AMULET_OF_WATER: Item = Item::new(
         100,
         "Amulet of Water",
         Glyph::Water,
         ItemAbility::Augment(Glyph::Water, 1),
-    );
+    ).also(ItemAbility::Ward(Glyph::Water, 50));
     pub const AMULET_OF_AIR: Item = Item::new(
         100,
         "Amulet of Air",
         Glyph::Air,
         ItemAbility::Augment(Glyph::Air, 1),
-    );
+    ).also(ItemAbility::Ward(Glyph::Air, 50));
     pub const AMULET_OF_EARTH: Item = Item::new(
         100,
         "Amulet of Earth",
         Glyph::Earth,
         ItemAbility::Augment(Glyph::Earth, 1),
-    );
+    ).also(ItemAbility::Ward(Glyph::Earth, 50));
     pub const AMULET_OF_VOID: Item = Item::new(
         100,
         "Amulet of Void",
         Glyph::Void,
         ItemAbility::Augment(Glyph::Void, 1),
-    );
+    ).also(ItemAbility::Ward(Glyph::Void, 50));
 
     pub const ALL: [Item; 5] = [
         AMULET_OF_FIRE,
@@ -109,6 +155,10 @@ pub mod amulets {
         AMULET_OF_EARTH,
         AMULET_OF_VOID,
     ];
+
+    pub fn by_name(name: &str) -> Option<Item> {
+        ALL.iter().find(|amulet| amulet.name == name).cloned()
+    }
 }
 
 pub mod scrolls {
@@ -162,31 +212,31 @@ pub mod potions {
         "Potion of Fire",
         Glyph::Fire,
         ItemAbility::Augment(Glyph::Fire, 1),
-    );
+    ).also(ItemAbility::Restore(15));
     pub const POTION_OF_WATER: Item = Item::new(
         100,
         "Potion of Water",
         Glyph::Water,
         ItemAbility::Augment(Glyph::Water, 1),
-    );
+    ).also(ItemAbility::Restore(15));
     pub const POTION_OF_AIR: Item = Item::new(
         100,
         "Potion of Air",
         Glyph::Air,
         ItemAbility::Augment(Glyph::Air, 1),
-    );
+    ).also(ItemAbility::Restore(15));
     pub const POTION_OF_EARTH: Item = Item::new(
         100,
         "Potion of Earth",
         Glyph::Earth,
         ItemAbility::Augment(Glyph::Earth, 1),
-    );
+    ).also(ItemAbility::Restore(15));
     pub const POTION_OF_VOID: Item = Item::new(
         100,
         "Potion of Void",
         Glyph::Void,
         ItemAbility::Augment(Glyph::Void, 1),
-    );
+    ).also(ItemAbility::Restore(15));
 
     pub const ALL: [Item; 5] = [
         POTION_OF_FIRE,
@@ -6,10 +6,13 @@ use super::c::e::spell_book::SpellBook;
 use super::c::e::Glyph;
 
 use items::Item;
+use battle::Wave;
 
 pub mod items;
 pub mod quests;
 pub mod battle;
+pub mod replay;
+pub mod observer;
 
 #[derive(PartialEq, Eq, Clone)]
 pub enum ItemType {
@@ -36,7 +39,7 @@ pub enum Reward {
 pub enum Objective {
     Kill { kind: MonsterType, count: u32 },
     Find { item: ItemType },
-    Free { wizard: Wizard },
+    Free { wizard: Box<Wizard> },
 }
 
 #[derive(PartialEq, Eq, Clone)]
@@ -45,5 +48,6 @@ pub struct Quest {
     name: String,
     objectives: Vec<Objective>,
     rewards: Vec<Reward>,
+    waves: Vec<Wave>, // reinforcements for the fight against the Kill objectives
     is_complete: bool,
 }
@@ -0,0 +1,219 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+use crate::a::c::e::{spell::Spell, status::Status, Glyph};
+use super::battle::{BattleAtom, BattleEvent, BattleMut};
+
+// Which side an event is about: the caster's for casts, the one hit, killed or changed for the rest.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Side {
+    Wizard,
+    Monster,
+}
+
+// Told about everything that goes into a battle's log, in the order it happens. Indices are into
+// the battle's allies for Side::Wizard and its enemies for Side::Monster.
+pub trait BattleObserver: std::fmt::Debug {
+    fn on_cast(&mut self, _side: Side, _caster: usize, _spell: &Spell) {}
+    fn on_damage(&mut self, _side: Side, _damager: usize, _damagee: usize, _damage: u16, _glyph: &Glyph) {}
+    fn on_kill(&mut self, _side: Side, _killer: usize, _killed: usize) {}
+    fn on_status(&mut self, _side: Side, _statusee: usize, _status: &Status, _gained: bool) {}
+    fn on_end(&mut self, _outcome: &BattleEvent, _ticks: usize) {}
+}
+
+// Whoever a battle reports to. They are neither saved nor copied: a loaded or cloned battle starts
+// without any, so no observer hears the same battle twice.
+#[derive(Default)]
+pub struct Observers(Vec<Rc<RefCell<dyn BattleObserver>>>);
+
+impl Observers {
+    pub fn new() -> Observers {
+        Observers::default()
+    }
+
+    pub fn push(&mut self, observer: Rc<RefCell<dyn BattleObserver>>) {
+        self.0.push(observer);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Rc<RefCell<dyn BattleObserver>>> {
+        self.0.iter()
+    }
+}
+
+impl Clone for Observers {
+    fn clone(&self) -> Observers {
+        Observers::new()
+    }
+}
+
+impl std::fmt::Debug for Observers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} observers", self.0.len())
+    }
+}
+
+pub fn notify(observer: &mut dyn BattleObserver, event: &BattleEvent, ticks: usize) {
+    let (side, atom) = match event {
+        BattleEvent::Wizard(atom) => (Side::Wizard, atom),
+        BattleEvent::Monster(atom) => (Side::Monster, atom),
+        outcome => return observer.on_end(outcome, ticks),
+    };
+    match atom {
+        BattleAtom::CastSpell(caster, spell) => observer.on_cast(side, *caster, spell),
+        BattleAtom::Mutation(BattleMut::Damage(damager, damagee, damage, glyph)) =>
+            observer.on_damage(side, *damager, *damagee, *damage, glyph),
+        BattleAtom::Kill(killer, killed) => observer.on_kill(side, *killer, *killed),
+        BattleAtom::Mutation(BattleMut::IncurStatus(_, statusee, status, _, _)) => observer.on_status(side, *statusee, status, true),
+        BattleAtom::Mutation(BattleMut::LoseStatus(_, statusee, status)) => observer.on_status(side, *statusee, status, false),
+        _ => {}
+    }
+}
+
+// Writes the battle out through the game's logger.
+#[derive(Default, Debug)]
+pub struct LogObserver;
+
+impl BattleObserver for LogObserver {
+    fn on_cast(&mut self, side: Side, caster: usize, spell: &Spell) {
+        log::info!("{:?} {} casts {}", side, caster, spell.name);
+    }
+    fn on_damage(&mut self, side: Side, damager: usize, damagee: usize, damage: u16, glyph: &Glyph) {
+        log::info!("{:?} {} takes {} {:?} damage from {}", side, damagee, damage, glyph, damager);
+    }
+    fn on_kill(&mut self, side: Side, killer: usize, killed: usize) {
+        log::info!("{:?} {} is killed by {}", side, killed, killer);
+    }
+    fn on_status(&mut self, side: Side, statusee: usize, status: &Status, gained: bool) {
+        log::debug!("{:?} {} {} {:?}", side, statusee, if gained { "gains" } else { "loses" }, status);
+    }
+    fn on_end(&mut self, outcome: &BattleEvent, ticks: usize) {
+        log::info!("{:?} after {} ticks", outcome, ticks);
+    }
+}
+
+// Running totals for the whole battle, by the side they happened to.
+#[derive(Default, Debug)]
+pub struct StatsObserver {
+    pub casts: HashMap<(Side, &'static str), u32>,
+    pub damage_taken: HashMap<Side, u64>,
+    pub kills: HashMap<Side, u32>, // of that side
+    pub statuses: HashMap<Side, u32>, // gained by that side
+    pub outcome: Option<BattleEvent>,
+    pub ticks: usize,
+}
+
+impl BattleObserver for StatsObserver {
+    fn on_cast(&mut self, side: Side, _caster: usize, spell: &Spell) {
+        *self.casts.entry((side, spell.name)).or_insert(0) += 1;
+    }
+    fn on_damage(&mut self, side: Side, _damager: usize, _damagee: usize, damage: u16, _glyph: &Glyph) {
+        *self.damage_taken.entry(side).or_insert(0) += damage as u64;
+    }
+    fn on_kill(&mut self, side: Side, _killer: usize, _killed: usize) {
+        *self.kills.entry(side).or_insert(0) += 1;
+    }
+    fn on_status(&mut self, side: Side, _statusee: usize, _status: &Status, gained: bool) {
+        if gained {
+            *self.statuses.entry(side).or_insert(0) += 1;
+        }
+    }
+    fn on_end(&mut self, outcome: &BattleEvent, ticks: usize) {
+        self.outcome = Some(outcome.clone());
+        self.ticks = ticks;
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Cue {
+    Cast(Side, usize, Glyph),
+    Hit(Side, usize, u16),
+    Fall(Side, usize),
+    Aura(Side, usize, Status), // a status to start showing
+    Fade(Side, usize, Status), // a status to stop showing
+    Curtain(BattleEvent), // the outcome
+}
+
+// Queues up what the renderer should play, for it to take at its own pace.
+#[derive(Default, Debug)]
+pub struct AnimationObserver {
+    cues: VecDeque<Cue>,
+}
+
+impl AnimationObserver {
+    pub fn next_cue(&mut self) -> Option<Cue> {
+        self.cues.pop_front()
+    }
+}
+
+impl BattleObserver for AnimationObserver {
+    fn on_cast(&mut self, side: Side, caster: usize, spell: &Spell) {
+        self.cues.push_back(Cue::Cast(side, caster, spell.glyph.0.clone()));
+    }
+    fn on_damage(&mut self, side: Side, _damager: usize, damagee: usize, damage: u16, _glyph: &Glyph) {
+        self.cues.push_back(Cue::Hit(side, damagee, damage));
+    }
+    fn on_kill(&mut self, side: Side, _killer: usize, killed: usize) {
+        self.cues.push_back(Cue::Fall(side, killed));
+    }
+    fn on_status(&mut self, side: Side, statusee: usize, status: &Status, gained: bool) {
+        self.cues.push_back(if gained {
+            Cue::Aura(side, statusee, status.clone())
+        } else {
+            Cue::Fade(side, statusee, status.clone())
+        });
+    }
+    fn on_end(&mut self, outcome: &BattleEvent, _ticks: usize) {
+        self.cues.push_back(Cue::Curtain(outcome.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a::c::{Colosseum, ColosseumArena};
+    use crate::a::c::e::{mon::{Monster, MonsterType}, spell::spells, wiz::Wizard};
+    use crate::a::q::battle::Battle;
+
+    fn goblin_fight(col: &mut Colosseum) -> Battle {
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.add_spell_to_book(spells::by_name("fireball").unwrap());
+        let wizard = col.insert(wizard);
+        let goblin = col.insert(Monster::new("Goblin", &MonsterType::Goblin, 1));
+        Battle::new(vec![wizard], vec![goblin], 5)
+    }
+
+    #[test]
+    fn stats_follow_the_battle() {
+        let stats = Rc::new(RefCell::new(StatsObserver::default()));
+        let mut col = Colosseum::headless(5);
+        let mut battle = goblin_fight(&mut col).with_observer(stats.clone());
+        let outcome = battle.run(&mut col);
+        let stats = stats.borrow();
+        assert_eq!(stats.outcome, Some(outcome));
+        assert_eq!(stats.ticks, battle.past_ticks.len() - 1);
+        assert!(stats.casts[&(Side::Wizard, "fireball")] > 0);
+        assert_eq!(stats.kills[&Side::Monster], 1);
+    }
+
+    #[test]
+    fn a_copy_of_a_battle_reports_to_no_one() {
+        let stats = Rc::new(RefCell::new(StatsObserver::default()));
+        let mut col = Colosseum::headless(5);
+        let battle = goblin_fight(&mut col).with_observer(stats.clone());
+        let mut copy = battle.clone();
+        assert_eq!(battle.observers.len(), 1);
+        assert!(copy.observers.is_empty());
+        copy.run(&mut col);
+        assert_eq!(stats.borrow().outcome, None);
+        assert!(stats.borrow().casts.is_empty());
+    }
+}
@@ -1,7 +1,7 @@
-use generational_arena::{Arena, Index};
+use generational_arena::Index;
 use rand::Rng;
 
-use super::battle::Battle;
+use super::battle::{Battle, Wave};
 use super::items::{amulets, books, misc, potions, rings, scrolls, tools};
 use super::{ItemType, Objective, Quest, Reward};
 use crate::a::c::Colosseum;
@@ -11,6 +11,8 @@ use crate::a::c::e::spell::{Spell, spells};
 use crate::a::c::e::spell_book::SpellBook;
 use crate::a::c::e::wiz::{Acceptance, Wizard};
 use crate::a::c::e::{Glyph, Style};
+use crate::a::realms::RealmLocation;
+use crate::a::rng::GameRng;
 
 impl Quest {
     pub fn is_complete(&self) -> bool {
@@ -34,14 +36,14 @@ impl Quest {
         false
     }
 
-    pub fn monsters(&self, col: &mut Colosseum) -> Vec<Index> {
+    pub fn monsters(&self, col: &mut Colosseum, location: &RealmLocation, rng: &mut GameRng) -> Vec<Index> {
         use crate::a::c::ColosseumArena;
         let mut monsters = Vec::new();
         for objective in &self.objectives {
             match objective {
                 Objective::Kill { kind, count } => {
                     for _ in 0..*count {
-                        let mon_id = col.insert(Monster::new(synonym::for_first_name(), kind, 1));
+                        let mon_id = col.insert(Monster::new(synonym::for_first_name(rng), kind, location.monster_level(rng)));
                         monsters.push(mon_id);
                     }
                 }
@@ -51,27 +53,44 @@ impl Quest {
         monsters
     }
 
-    pub fn win_battle(&mut self, wizards: &mut Arena<Wizard>, monsters: &mut Arena<Monster>, party: &mut Party, battle: &Battle) {
-        for enemy in battle.enemies.iter() {
-            monsters.remove(*enemy);            
-        }
+    // The quest only knows what comes in which wave, how strong it is depends on where it is fought.
+    pub fn waves(&self, location: &RealmLocation, rng: &mut GameRng) -> Vec<Wave> {
+        self.waves.iter()
+            .map(|wave| Wave {
+                tick: wave.tick,
+                monsters: wave.monsters.iter().map(|(kind, _)| (kind.clone(), location.monster_level(rng))).collect(),
+            })
+            .collect()
+    }
+
+    pub fn win_battle(&mut self, col: &mut Colosseum, _party: &mut Party, battle: &Battle) {
+        Self::clear_battle(col, battle);
         //let hashset = battle.allies.into_iter().filter(|i| !battle.active_allies.contains(i) && wizards.remove(*i).is_some()).collect::<HashSet<Index>>();
         //party.members.retain(|i| !hashset.contains(i));
         self.is_complete = true;
     }
 
-    pub fn lose_battle(&mut self, wizards: &mut Arena<Wizard>, monsters: &mut Arena<Monster>, party: &mut Party, battle: &Battle) {
-        for enemy in battle.enemies.iter() {
-            monsters.remove(*enemy);            
-        }
+    pub fn lose_battle(&mut self, col: &mut Colosseum, _party: &mut Party, battle: &Battle) {
+        Self::clear_battle(col, battle);
         //let hashset = battle.allies.into_iter().filter(|i| !battle.active_allies.contains(i) && wizards.remove(*i).is_some()).collect::<HashSet<Index>>();
         //party.members.retain(|i| !hashset.contains(i));
         self.is_complete = true;
     }
 
+    // The survivors got away, so the quest stays open to be attempted again.
+    pub fn retreat_battle(&mut self, col: &mut Colosseum, _party: &mut Party, battle: &Battle) {
+        Self::clear_battle(col, battle);
+    }
+
+    fn clear_battle(col: &mut Colosseum, battle: &Battle) {
+        use crate::a::c::ColosseumArena;
+        for enemy in battle.enemies.iter() {
+            (col as &mut dyn ColosseumArena<Monster>).remove(*enemy);
+        }
+    }
+
 
-    pub fn generate() -> Quest {
-        let mut rng = rand::thread_rng();
+    pub fn generate(rng: &mut GameRng) -> Quest {
         let acceptance = Acceptance::from_style(
             match rng.gen_range(0, 5) {
                 0 => Style::Elder,
@@ -82,37 +101,39 @@ impl Quest {
             },
             1,
         );
-        let objectives = Self::generate_objectives(&acceptance);
+        let objectives = Self::generate_objectives(&acceptance, rng);
+        let mut id_bytes = [0u8; 16];
+        rng.fill(&mut id_bytes);
         Quest {
-            id: uuid::Uuid::new_v4(),
-            name: Self::generate_name(),
-            rewards: Self::generate_rewards(&objectives, &acceptance),
+            id: uuid::Builder::from_random_bytes(id_bytes).into_uuid(),
+            name: Self::generate_name(rng),
+            rewards: Self::generate_rewards(&objectives, &acceptance, rng),
+            waves: Self::generate_waves(&objectives, rng),
             objectives,
             is_complete: false,
         }
     }
 
-    pub fn generate_name() -> String {
-        synonym::for_quest()
-            + synonym::for_of_the()
-            + synonym::for_adjective()
-            + synonym::for_noun()
+    pub fn generate_name(rng: &mut GameRng) -> String {
+        synonym::for_quest(rng)
+            + synonym::for_of_the(rng)
+            + synonym::for_adjective(rng)
+            + synonym::for_noun(rng)
     }
 
-    fn generate_objectives(acceptance: &Acceptance) -> Vec<Objective> {
+    fn generate_objectives(acceptance: &Acceptance, rng: &mut GameRng) -> Vec<Objective> {
         let mut objectives = Vec::new();
-        let mut rng = rand::thread_rng();
         let count = rng.gen_range(1, 4);
         for _ in 0..count {
             let objective = match rng.gen_range(0, 3) {
                 0 => Objective::Find {
-                    item: Self::generate_item(acceptance),
+                    item: Self::generate_item(acceptance, rng),
                 },
                 1 => Objective::Free {
-                    wizard: Self::generate_wizard(acceptance),
+                    wizard: Box::new(Self::generate_wizard(acceptance, rng)),
                 },
                 _ => {
-                    let monster_type = Self::generate_monster_type();
+                    let monster_type = Self::generate_monster_type(rng);
                     Objective::Kill {
                         kind: monster_type.clone(),
                         count: match monster_type.difficulty() {
@@ -129,13 +150,28 @@ impl Quest {
         objectives
     }
 
-    fn generate_monster_type() -> MonsterType {
-        let mut rng = rand::thread_rng();
-        crate::a::c::e::mon::ALL[rng.gen_range(0, crate::a::c::e::mon::ALL.len())].clone()
+    // Now and then more of the quarry turns up a few ticks into the fight.
+    fn generate_waves(objectives: &Vec<Objective>, rng: &mut GameRng) -> Vec<Wave> {
+        let mut waves = Vec::new();
+        for objective in objectives {
+            if let Objective::Kill { kind, count } = objective {
+                if rng.gen_range(0, 4) == 0 {
+                    waves.push(Wave {
+                        tick: rng.gen_range(3, 8),
+                        monsters: vec![(kind.clone(), 1); (*count as usize / 2).max(1)],
+                    });
+                }
+            }
+        }
+        waves
     }
 
-    fn generate_item(_acceptance: &Acceptance) -> ItemType {
-        let mut rng = rand::thread_rng();
+    fn generate_monster_type(rng: &mut GameRng) -> MonsterType {
+        let monsters = crate::a::c::e::mon::registry();
+        monsters.iter().nth(rng.gen_range(0, monsters.len())).unwrap().kind.clone()
+    }
+
+    fn generate_item(_acceptance: &Acceptance, rng: &mut GameRng) -> ItemType {
         match rng.gen_range(0, 7) {
             0 => ItemType::Ring(rings::ALL[rng.gen_range(0, 5)].clone()),
             1 => ItemType::Amulet(amulets::ALL[rng.gen_range(0, 5)].clone()),
@@ -147,30 +183,27 @@ impl Quest {
         }
     }
 
-    fn generate_wizard(acceptance: &Acceptance) -> Wizard {
-        let _rng = rand::thread_rng();
-        let mut wizard = Wizard::new(Self::generate_wizard_name());
-        Self::generate_affinity(&mut wizard);
-        Self::generate_acceptance(&mut wizard, acceptance);
+    fn generate_wizard(acceptance: &Acceptance, rng: &mut GameRng) -> Wizard {
+        let mut wizard = Wizard::new(Self::generate_wizard_name(rng));
+        Self::generate_affinity(&mut wizard, rng);
+        Self::generate_acceptance(&mut wizard, acceptance, rng);
         wizard
     }
 
-    fn generate_wizard_name() -> String {
-        let mut rng = rand::thread_rng();
+    fn generate_wizard_name(rng: &mut GameRng) -> String {
         let mut name = String::new();
         let count = rng.gen_range(1, 3);
         for _ in 0..count {
-            name.push_str(synonym::for_first_name());
+            name.push_str(synonym::for_first_name(rng));
             name.push_str(" ");
         }
-        name.push_str(synonym::for_last_name());
+        name.push_str(synonym::for_last_name(rng));
         name.push_str(" ");
-        name.push_str(synonym::for_adjective());
+        name.push_str(synonym::for_adjective(rng));
         name
     }
 
-    fn generate_affinity(wizard: &mut Wizard) {
-        let mut rng = rand::thread_rng();
+    fn generate_affinity(wizard: &mut Wizard, rng: &mut GameRng) {
         for _i in 0..rng.gen_range(5, 10) {
             match rng.gen_range(0, 5) {
                 0 => wizard.affinity.fire += 1,
@@ -182,8 +215,7 @@ impl Quest {
         }
     }
 
-    fn generate_acceptance(wizard: &mut Wizard, acceptance: &Acceptance) {
-        let mut rng = rand::thread_rng();
+    fn generate_acceptance(wizard: &mut Wizard, acceptance: &Acceptance, rng: &mut GameRng) {
         for _i in 0..rng.gen_range(0, 2) {
             match rng.gen_range(0, 5) {
                 0 => wizard.acceptance.elder += 1,
@@ -196,9 +228,8 @@ impl Quest {
         wizard.acceptance.add(acceptance);
     }
 
-    fn generate_rewards(objectives: &Vec<Objective>, acceptance: &Acceptance) -> Vec<Reward> {
+    fn generate_rewards(objectives: &Vec<Objective>, acceptance: &Acceptance, rng: &mut GameRng) -> Vec<Reward> {
         let mut rewards = Vec::new();
-        let mut rng = rand::thread_rng();
 
         let mut num_of_rewards = 1;
         for objective in objectives {
@@ -214,33 +245,30 @@ impl Quest {
         for _ in 0..num_of_rewards {
             let reward = match rng.gen_range(0, 5) {
                 0 => Reward::Gold(rng.gen_range(1, 100)),
-                1 => Reward::Item(Self::generate_item(acceptance)),
-                2 => Reward::SpellBook(Self::generate_spell_book(acceptance)),
-                3 => Reward::Glyph(Self::generate_glyph(acceptance), rng.gen_range(1, 4)),
-                _ => Reward::Learn(Self::generate_spell(acceptance)),
+                1 => Reward::Item(Self::generate_item(acceptance, rng)),
+                2 => Reward::SpellBook(Self::generate_spell_book(acceptance, rng)),
+                3 => Reward::Glyph(Self::generate_glyph(acceptance, rng), rng.gen_range(1, 4)),
+                _ => Reward::Learn(Self::generate_spell(acceptance, rng)),
             };
             rewards.push(reward);
         }
         rewards
     }
 
-    fn generate_spell_book(acceptance: &Acceptance) -> SpellBook {
-        let mut rng = rand::thread_rng();
+    fn generate_spell_book(acceptance: &Acceptance, rng: &mut GameRng) -> SpellBook {
         let mut spell_book = SpellBook::new();
         for _ in 0..rng.gen_range(1, 5) {
-            spell_book.add_spell(Self::generate_spell(acceptance));
+            spell_book.add_spell(Self::generate_spell(acceptance, rng));
         }
         spell_book
     }
 
-    fn generate_spell(acceptance: &Acceptance) -> Spell {
-        let mut rng = rand::thread_rng();
-        let choices = &spells::BY_STYLE[acceptance.get_highest()];
+    fn generate_spell(acceptance: &Acceptance, rng: &mut GameRng) -> Spell {
+        let choices = spells::registry().by_style(acceptance.get_highest());
         choices[rng.gen_range(0, choices.len())].clone()
     }
 
-    fn generate_glyph(_acceptance: &Acceptance) -> Glyph {
-        let mut rng = rand::thread_rng();
+    fn generate_glyph(_acceptance: &Acceptance, rng: &mut GameRng) -> Glyph {
         match rng.gen_range(0, 5) {
             0 => Glyph::Fire,
             1 => Glyph::Air,
@@ -253,38 +281,33 @@ impl Quest {
 
 pub mod synonym {
     use rand::Rng;
-    pub fn for_quest() -> String {
-        let mut rng = rand::thread_rng();
+    use crate::a::rng::GameRng;
+    pub fn for_quest(rng: &mut GameRng) -> String {
         let index = rng.gen_range(0, QUESTS.len());
         QUESTS[index].to_string()
     }
 
-    pub fn for_of_the() -> &'static str {
-        let mut rng = rand::thread_rng();
+    pub fn for_of_the(rng: &mut GameRng) -> &'static str {
         let index = rng.gen_range(0, OF_THE.len());
         OF_THE[index]
     }
 
-    pub fn for_adjective() -> &'static str {
-        let mut rng = rand::thread_rng();
+    pub fn for_adjective(rng: &mut GameRng) -> &'static str {
         let index = rng.gen_range(0, ADJECTIVES.len());
         ADJECTIVES[index]
     }
 
-    pub fn for_noun() -> &'static str {
-        let mut rng = rand::thread_rng();
+    pub fn for_noun(rng: &mut GameRng) -> &'static str {
         let index = rng.gen_range(0, NOUNS.len());
         NOUNS[index]
     }
 
-    pub fn for_first_name() -> &'static str {
-        let mut rng = rand::thread_rng();
+    pub fn for_first_name(rng: &mut GameRng) -> &'static str {
         let index = rng.gen_range(0, FIRST_NAME.len());
         FIRST_NAME[index]
     }
 
-    pub fn for_last_name() -> &'static str {
-        let mut rng = rand::thread_rng();
+    pub fn for_last_name(rng: &mut GameRng) -> &'static str {
         let index = rng.gen_range(0, LAST_NAME.len());
         LAST_NAME[index]
     }
@@ -382,3 +405,32 @@ pub mod synonym {
 
     const LAST_NAME: [&str; 5] = ["Bilgebottom", "Butterbur", "Cotton", "Wayne", "Biddi"];
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a::c::ColosseumArena;
+    use crate::a::c::e::wiz::MindSet;
+    use crate::a::q::battle::BattleEvent;
+
+    #[test]
+    fn a_retreat_leaves_the_quest_open_and_the_party_whole() {
+        let mut col = Colosseum::headless(7);
+        let mut quest = Quest::generate(&mut GameRng::new(7));
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.state = MindSet::Coward;
+        wizard.hp = 20;
+        let wizard = col.insert(wizard);
+        let mut party = Party::new(vec![wizard]);
+        let slime = col.insert(Monster::new("Slime", &MonsterType::Slime, 1));
+        let mut battle = Battle::new(vec![wizard], vec![slime], 7).with_tick_limit(20);
+        assert_eq!(battle.run(&mut col), BattleEvent::Retreat);
+
+        quest.retreat_battle(&mut col, &mut party, &battle);
+
+        assert!(!quest.is_complete());
+        assert_eq!(party.members, vec![wizard]);
+        assert!(ColosseumArena::<Wizard>::get_arena(&col).contains(wizard));
+        assert!(!ColosseumArena::<Monster>::get_arena(&col).contains(slime));
+    }
+}
@@ -0,0 +1,246 @@
+use std::fs::File;
+use std::io::{Cursor, Read, Write};
+
+use crate::a::c::e::mon::Monster;
+use crate::a::c::e::status::StatusSet;
+use crate::a::c::inp::Inputable;
+use crate::a::c::out::Outputable;
+
+use super::battle::{apply_mutation, Affectee, Battle, BattleAtom, BattleEntity, BattleEvent, Tick};
+
+pub const REPLAY_EXTENSION: &str = "replay";
+pub(crate) const REPLAY_MAGIC: &[u8; 4] = b"TKRP";
+pub(crate) const REPLAY_VERSION: u8 = 4;
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Combatant {
+    pub name: String,
+    pub hp: u32,
+    pub max_hp: u32,
+    pub status: StatusSet,
+}
+
+// A single battle on its own: who fought (as they were before the first tick) and
+// every tick that was recorded, so it can be shared and stepped through later.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Replay {
+    pub seed: u64,
+    pub allies: Vec<Combatant>,
+    pub enemies: Vec<Combatant>,
+    pub ticks: Vec<Tick>,
+}
+
+impl Replay {
+    pub fn from_battle(battle: &Battle) -> Replay {
+        Replay {
+            seed: battle.seed,
+            allies: battle.opening_allies.clone(),
+            enemies: battle.opening_enemies.clone(),
+            ticks: battle.past_ticks.clone(),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let mut f = File::create(path)?;
+        f.write_all(&self.as_bytes()?)?;
+        f.flush()
+    }
+
+    pub fn load(path: &str) -> std::io::Result<Replay> {
+        let mut buf = Vec::new();
+        File::open(path)?.read_to_end(&mut buf)?;
+        Replay::from_bytes(&mut Cursor::new(&buf))
+    }
+
+    pub fn len(&self) -> usize {
+        self.ticks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ticks.is_empty()
+    }
+}
+
+pub struct ReplayPlayer {
+    replay: Replay,
+    position: usize,
+    allies: Vec<Combatant>,
+    enemies: Vec<Combatant>,
+    active_allies: Vec<bool>,
+    active_enemies: Vec<bool>,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> ReplayPlayer {
+        let allies = replay.allies.clone();
+        let enemies = replay.enemies.clone();
+        ReplayPlayer {
+            active_allies: vec![true; allies.len()],
+            active_enemies: vec![true; enemies.len()],
+            replay,
+            position: 0,
+            allies,
+            enemies,
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    // Number of ticks already applied; 0 is the state before the battle started.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.replay.len()
+    }
+
+    pub fn allies(&self) -> &Vec<Combatant> {
+        &self.allies
+    }
+
+    pub fn enemies(&self) -> &Vec<Combatant> {
+        &self.enemies
+    }
+
+    pub fn last_tick(&self) -> Option<&Tick> {
+        match self.position {
+            0 => None,
+            position => self.replay.ticks.get(position - 1),
+        }
+    }
+
+    pub fn step_forward(&mut self) -> Option<&Tick> {
+        if self.is_finished() {
+            return None;
+        }
+        self.apply(self.position);
+        self.position += 1;
+        self.last_tick()
+    }
+
+    pub fn step_backward(&mut self) -> Option<&Tick> {
+        if self.position == 0 {
+            return None;
+        }
+        self.seek(self.position - 1);
+        self.last_tick()
+    }
+
+    // Ticks only ever move forward, so going back means rebuilding from the opening.
+    pub fn seek(&mut self, position: usize) {
+        let position = position.min(self.replay.len());
+        if position < self.position {
+            self.rewind();
+        }
+        while self.position < position {
+            self.apply(self.position);
+            self.position += 1;
+        }
+    }
+
+    pub fn rewind(&mut self) {
+        self.allies = self.replay.allies.clone();
+        self.enemies = self.replay.enemies.clone();
+        self.active_allies = vec![true; self.allies.len()];
+        self.active_enemies = vec![true; self.enemies.len()];
+        self.position = 0;
+    }
+
+    fn apply(&mut self, index: usize) {
+        let tick = &self.replay.ticks[index];
+        if Self::is_outcome(tick) {
+            return;
+        }
+        for event in tick.iter() {
+            match event {
+                BattleEvent::Wizard(BattleAtom::Kill(_, killed))
+                | BattleEvent::Wizard(BattleAtom::Flee(killed)) => self.active_allies[*killed] = false,
+                BattleEvent::Monster(BattleAtom::Kill(_, killed))
+                | BattleEvent::Monster(BattleAtom::Flee(killed)) => self.active_enemies[*killed] = false,
+                // Newcomers are fresh monsters of their kind, named after it like in the battle.
+                BattleEvent::Monster(BattleAtom::Summon(_, kind, level))
+                | BattleEvent::Monster(BattleAtom::Reinforce(kind, level)) => {
+                    self.enemies.push(Monster::new(kind.name(), kind, *level).as_combatant());
+                    self.active_enemies.push(true);
+                }
+                _ => {}
+            }
+        }
+        // Battle::tick skips status upkeep on the tick that decides the fight.
+        let decided = self.replay.ticks.get(index + 1).is_some_and(Self::is_outcome);
+        if !decided {
+            for (combatant, active) in self.allies.iter_mut().zip(self.active_allies.iter()) {
+                if *active {
+                    combatant.status.tick_all();
+                }
+            }
+            for (combatant, active) in self.enemies.iter_mut().zip(self.active_enemies.iter()) {
+                if *active {
+                    combatant.status.tick_all();
+                }
+            }
+        }
+        for event in tick.iter() {
+            match event {
+                BattleEvent::Wizard(BattleAtom::Mutation(battle_mut)) => {
+                    apply_mutation(battle_mut, &mut self.allies[battle_mut.affectee()]);
+                }
+                BattleEvent::Monster(BattleAtom::Mutation(battle_mut)) => {
+                    apply_mutation(battle_mut, &mut self.enemies[battle_mut.affectee()]);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn is_outcome(tick: &Tick) -> bool {
+        tick.iter().any(|event| event.is_outcome())
+    }
+}
+
+impl Affectee for Combatant {
+    fn hp(&self) -> u32 { self.hp }
+    fn set_hp(&mut self, hp: u32) { self.hp = hp; }
+    fn max_hp(&self) -> u32 { self.max_hp }
+    fn status_mut(&mut self) -> &mut StatusSet { &mut self.status }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a::c::{Colosseum, ColosseumArena};
+    use crate::a::c::e::mon::MonsterType;
+    use crate::a::c::e::spell::spells;
+    use crate::a::c::e::wiz::Wizard;
+
+    #[test]
+    fn a_saved_replay_plays_back_the_battle() {
+        let mut col = Colosseum::headless(8);
+        let mut wizard = Wizard::new("Bob".to_string());
+        wizard.add_spell_to_book(spells::by_name("fireball").unwrap());
+        let wizard = col.insert(wizard);
+        let orc = col.insert(Monster::new("Grub", &MonsterType::Orc, 2));
+        let mut battle = Battle::new(vec![wizard], vec![orc], 8);
+        battle.run(&mut col);
+
+        let replay = Replay::from_battle(&battle);
+        let loaded = Replay::from_bytes(&mut Cursor::new(&replay.as_bytes().unwrap())).unwrap();
+        assert_eq!(loaded, replay);
+
+        let mut player = ReplayPlayer::new(loaded);
+        player.seek(usize::MAX);
+        assert!(player.is_finished());
+        let wizard: &Wizard = col.get(wizard);
+        let orc: &Monster = col.get(orc);
+        assert_eq!(player.allies()[0].hp, wizard.hp);
+        assert_eq!(player.enemies()[0].hp, orc.hp);
+
+        player.step_backward();
+        assert_eq!(player.position(), battle.past_ticks.len() - 1);
+        player.rewind();
+        assert_eq!(player.allies()[0].hp, wizard.max_hp);
+    }
+}
@@ -1,16 +1,22 @@
+use generational_arena::Index;
+use rand::{Rng, RngCore};
 use uuid::Uuid;
 
 use crate::a::c::Colosseum;
+use crate::a::c::e::boss;
 use crate::a::c::e::mon::Monster;
 use crate::a::c::e::wiz::Wizard;
 use crate::a::q::battle::{Battle, Tick, BattleEvent};
+use crate::a::q::quests::synonym;
+use crate::a::rng::GameRng;
 
-use super::super::{q::Quest, c::e::party::Party};
+use super::super::{q::{ItemType, Quest}, c::e::party::Party};
 use super::RealmLocation;
 
 enum AdventureEndEvent {
     Success,
     Failure,
+    Retreat,
 }
 enum AdventureEvent {
     Start(Uuid),// party
@@ -27,6 +33,7 @@ pub struct Adventure {
     pub quests: Vec<Quest>,
     pub location: RealmLocation,
     pub party: Option<Party>,
+    pub inventory: Vec<ItemType>,
     events: Vec<AdventureEvent>,
     current_battle: Option<Battle>,
     battles: Vec<Battle>,
@@ -39,6 +46,7 @@ impl Adventure {
             name,
             location,
             party: None,
+            inventory: Vec::new(),
             current_quest: None,
             quests,
             events: Vec::new(),
@@ -53,11 +61,48 @@ impl Adventure {
         self.party = Some(party);
     }
 
+    // Hangs the amulet at the given place in the inventory around a member's neck; anything else stays packed.
+    // Whatever the member wore before goes back in the pack.
+    pub fn equip(&mut self, col: &mut Colosseum, member: Index, at: usize) -> bool {
+        use crate::a::c::ColosseumArena;
+        match self.inventory.get(at) {
+            Some(ItemType::Amulet(amulet)) if amulet.is_wearable() => {}
+            _ => return false,
+        }
+        let ItemType::Amulet(amulet) = self.inventory.remove(at) else { unreachable!() };
+        let wizard: &mut Wizard = col.get_mut(member);
+        if let Some(worn) = wizard.wear(amulet) {
+            self.inventory.push(ItemType::Amulet(worn));
+        }
+        true
+    }
+
+    // Packs away whatever the member wears; returns whether there was anything.
+    pub fn unequip(&mut self, col: &mut Colosseum, member: Index) -> bool {
+        use crate::a::c::ColosseumArena;
+        let wizard: &mut Wizard = col.get_mut(member);
+        match wizard.take_off() {
+            Some(amulet) => {
+                self.inventory.push(ItemType::Amulet(amulet));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        matches!(self.events.last(), Some(AdventureEvent::End(_)))
+    }
+
     pub fn execute(&mut self, col: &mut Colosseum) {
+        if self.is_over() {
+            return;
+        }
         if self.current_quest.is_none() {
             self.current_quest = Some(0);
             self.events.push(AdventureEvent::BeginQuest(self.current_quest.unwrap()));
         }
+        let last_quest = self.quests.len() - 1;
         let mut quest = &mut self.quests[self.current_quest.unwrap()];
         while quest.is_complete() {
             if self.current_quest.unwrap() == self.quests.len() - 1 {
@@ -72,29 +117,136 @@ impl Adventure {
 
         if quest.is_battle() {
             if self.current_battle.is_none() {
+                let mut rng = col.rng_mut().fork();
+                let seed = col.rng_mut().next_u64();
+                let party = self.party.as_ref().unwrap();
+                Self::drink_potions(col, party, &mut self.inventory);
+                let mut monsters = quest.monsters(col, &self.location, &mut rng);
+                if let RealmLocation::Dungeon { .. } = self.location {
+                    if self.current_quest.unwrap() == last_quest {
+                        monsters.push(Self::capstone(col, &self.location, &mut rng));
+                    }
+                }
+                let waves = quest.waves(&self.location, &mut rng);
                 let battle = Battle::new(
-                    self.party.as_ref().unwrap().members.clone(), 
-                quest.monsters(col));
+                    party.members.clone(), 
+                monsters,
+                seed).in_formation(party).with_waves(waves).with_variance();
                 self.events.push(AdventureEvent::BeginBattle(self.battles.len()));
-                self.battles.push(battle);
+                self.current_battle = Some(battle);
             }
 
             if self.current_battle.is_some() {
                 self.tick = self.current_battle.as_mut().unwrap().tick(&mut self.tick, col);
             }
-/*
-            if let Some(event) = self.tick.iter().find(|evnt| *evnt == &BattleEvent::Victory || *evnt == &BattleEvent::Defeat) {
-                if let BattleEvent::Victory = event {
-                    quest.win_battle(wizards, monsters, self.party.as_mut().unwrap(), self.current_battle.as_ref().unwrap());
-                } else {
-                    quest.lose_battle(wizards, monsters, self.party.as_mut().unwrap(), self.current_battle.as_ref().unwrap());
+
+            if let Some(event) = self.tick.iter().find(|evnt| evnt.is_outcome()).cloned() {
+                let battle = self.current_battle.take().unwrap();
+                let party = self.party.as_mut().unwrap();
+                match event {
+                    BattleEvent::Victory => {
+                        quest.win_battle(col, party, &battle);
+                    }
+                    // Neither side could finish the other, so the party walks away as if it had fled.
+                    BattleEvent::Retreat | BattleEvent::Draw => quest.retreat_battle(col, party, &battle),
+                    _ => quest.lose_battle(col, party, &battle),
                 }
                 self.events.push(AdventureEvent::BattleFinish(self.battles.len(), event.clone()));
-                self.current_battle = None;
+                self.battles.push(battle);
                 self.tick = Tick::new();
+                match event {
+                    BattleEvent::Retreat | BattleEvent::Draw => self.events.push(AdventureEvent::End(AdventureEndEvent::Retreat)),
+                    BattleEvent::Defeat => self.events.push(AdventureEvent::End(AdventureEndEvent::Failure)),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // Whoever sets out for a fight on less than half their mana drinks a potion, while the party has any.
+    fn drink_potions(col: &mut Colosseum, party: &Party, inventory: &mut Vec<ItemType>) {
+        use crate::a::c::ColosseumArena;
+        for member in party.members.iter() {
+            let wizard: &mut Wizard = col.get_mut(*member);
+            if wizard.mana * 2 >= wizard.max_mana {
+                continue;
+            }
+            if let Some(at) = inventory.iter().position(|item| matches!(item, ItemType::Potion(_))) {
+                if let ItemType::Potion(potion) = inventory.remove(at) {
+                    potion.drink(wizard);
+                }
             }
-*/
         }
     }
 
+    // The last fight of a dungeon is with whatever rules it, a level above anything else down there.
+    fn capstone(col: &mut Colosseum, location: &RealmLocation, rng: &mut GameRng) -> Index {
+        use crate::a::c::ColosseumArena;
+        let kind = &boss::CAPSTONES[rng.gen_range(0, boss::CAPSTONES.len())];
+        let level = location.difficulty().saturating_add(2);
+        col.insert(Monster::boss(&format!("{} the {}", synonym::for_first_name(rng), kind.name()), kind, level))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a::c::ColosseumArena;
+    use crate::a::c::e::Glyph;
+    use crate::a::q::battle::BattleEntity;
+    use crate::a::c::e::status::{Status, Wards};
+    use crate::a::q::items::{amulets, potions, rings};
+
+    #[test]
+    fn only_the_thirsty_drink() {
+        let mut col = Colosseum::headless(1);
+        let mut drained = Wizard::new("Bob".to_string());
+        drained.mana = 5;
+        let drained = col.insert(drained);
+        let rested = col.insert(Wizard::new("Rob".to_string()));
+        let party = Party::new(vec![drained, rested]);
+        let mut inventory = vec![
+            ItemType::Ring(rings::RING_OF_FIRE),
+            ItemType::Potion(potions::POTION_OF_FIRE),
+            ItemType::Potion(potions::POTION_OF_WATER),
+        ];
+
+        Adventure::drink_potions(&mut col, &party, &mut inventory);
+
+        assert_eq!(ColosseumArena::<Wizard>::get(&col, drained).mana, 20);
+        assert_eq!(ColosseumArena::<Wizard>::get(&col, rested).mana, 30);
+        assert!(inventory == vec![ItemType::Ring(rings::RING_OF_FIRE), ItemType::Potion(potions::POTION_OF_WATER)]);
+    }
+    #[test]
+    fn an_equipped_amulet_wards_its_wearer() {
+        let mut col = Colosseum::headless(1);
+        let bob = col.insert(Wizard::new("Bob".to_string()));
+        let mut adventure = Adventure::new("Walk".to_string(), Vec::new(), RealmLocation::City { name: "Ashford".to_string(), description: String::new(), difficulty: 1 });
+        adventure.inventory.push(ItemType::Ring(rings::RING_OF_FIRE));
+        adventure.inventory.push(ItemType::Amulet(amulets::AMULET_OF_FIRE));
+
+        assert!(!adventure.equip(&mut col, bob, 0));
+        assert!(adventure.equip(&mut col, bob, 1));
+        assert_eq!(adventure.inventory.len(), 1);
+        let wizard: &Wizard = col.get(bob);
+        assert_eq!(wizard.worn_wards().against(&Status::Burning, &Glyph::Fire), 50);
+        assert_eq!(wizard.worn_wards().against(&Status::Submerged, &Glyph::Water), 0);
+        assert_eq!(wizard.wards, Wards::new());
+
+        // A second amulet takes the place of the first rather than stacking on it.
+        adventure.inventory.push(ItemType::Amulet(amulets::AMULET_OF_WATER));
+        assert!(adventure.equip(&mut col, bob, 1));
+        assert!(adventure.inventory == vec![ItemType::Ring(rings::RING_OF_FIRE), ItemType::Amulet(amulets::AMULET_OF_FIRE)]);
+        let wizard: &Wizard = col.get(bob);
+        assert_eq!(wizard.worn_wards().against(&Status::Burning, &Glyph::Fire), 0);
+        assert_eq!(wizard.worn_wards().against(&Status::Submerged, &Glyph::Water), 50);
+        assert_eq!(wizard.as_target(0).wards, wizard.worn_wards());
+
+        assert!(adventure.unequip(&mut col, bob));
+        assert!(!adventure.unequip(&mut col, bob));
+        assert_eq!(adventure.inventory.len(), 3);
+        let wizard: &Wizard = col.get(bob);
+        assert_eq!(wizard.worn_wards(), Wards::new());
+    }
 }
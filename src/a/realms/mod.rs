@@ -2,7 +2,10 @@ pub mod adventures;
 
 use adventures::Adventure;
 
+use rand::Rng;
+
 use super::c::e::wiz::{Affinity, Acceptance};
+use super::rng::GameRng;
 
 pub enum RealmLocation {
     City {
@@ -22,6 +25,21 @@ pub enum RealmLocation {
     },
 }
 
+impl RealmLocation {
+    pub fn difficulty(&self) -> u8 {
+        match self {
+            Self::City { difficulty, .. }
+            | Self::Dungeon { difficulty, .. }
+            | Self::Wilderness { difficulty, .. } => *difficulty,
+        }
+    }
+
+    // Monsters met here are within a level of the location's difficulty, but never below the first.
+    pub fn monster_level(&self, rng: &mut GameRng) -> u8 {
+        (self.difficulty().saturating_add(rng.gen_range(0, 3))).saturating_sub(1).max(1)
+    }
+}
+
 pub struct Realm {
     pub name: String,
     pub description: String,
@@ -30,4 +48,33 @@ pub struct Realm {
     pub adventures: Vec<Adventure>,
     pub affinity: Affinity,
     pub acceptance: Acceptance,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wilderness(difficulty: u8) -> RealmLocation {
+        RealmLocation::Wilderness { name: "Moor".to_string(), description: String::new(), difficulty }
+    }
+
+    #[test]
+    fn monsters_are_within_a_level_of_the_location() {
+        let mut rng = GameRng::new(5);
+        let levels = (0..200).map(|_| wilderness(4).monster_level(&mut rng)).collect::<Vec<u8>>();
+        for level in 3..=5 {
+            assert!(levels.contains(&level), "{:?}", levels);
+        }
+        assert!(levels.iter().all(|level| (3..=5).contains(level)));
+    }
+
+    #[test]
+    fn monsters_are_never_below_the_first_level() {
+        let mut rng = GameRng::new(5);
+        for difficulty in 0..2 {
+            for _ in 0..50 {
+                let level = wilderness(difficulty).monster_level(&mut rng);
+                assert!(level >= 1 && level <= difficulty + 1, "{} at {}", level, difficulty);
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use rand::{Error, RngCore, SeedableRng};
+
+// SplitMix64: tiny, fast and fully described by a single u64, so the state can be
+// written straight into a save file and a fight/quest replays bit-for-bit from it.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    // The seed is the whole state, so a saved state() comes back through here as well.
+    pub fn new(seed: u64) -> Self {
+        GameRng { state: seed }
+    }
+
+    pub fn from_entropy() -> Self {
+        GameRng::new(rand::thread_rng().next_u64())
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    // Derives an independent generator (e.g. for a single battle) without
+    // consuming more than one value from this one.
+    pub fn fork(&mut self) -> GameRng {
+        GameRng::new(self.next_u64())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for GameRng {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        GameRng::new(u64::from_le_bytes(seed))
+    }
+
+    fn seed_from_u64(seed: u64) -> Self {
+        GameRng::new(seed)
+    }
+}
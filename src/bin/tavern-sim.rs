@@ -0,0 +1,281 @@
+#[macro_use]
+extern crate failure;
+extern crate rayon;
+
+// Only the headless engine is linked in, so the simulator never opens a window
+// or needs a GL context.
+use tavern_keeper::a;
+
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use a::c::{Colosseum, ColosseumArena};
+use a::c::e::Glyph;
+use a::c::e::mon::{self, Monster, MonsterType};
+use a::c::e::spell::{spells, Spell};
+use a::c::e::wiz::Wizard;
+use a::q::battle::{Battle, BattleAtom, BattleEvent, BattleMut, Wave, STALEMATE_TICKS, TICK_LIMIT};
+
+const USAGE: &str = "usage: tavern-sim --wizard <spell>[,<spell>...] [--wizard ...] --enemy <MonsterType>[:<difficulty>] [--enemy ...] [--boss <MonsterType>[:<difficulty>] ...] [--wave <tick>:<MonsterType>[:<difficulty>] ...] [--variance on|off] [--tick-limit <ticks>] [--stalemate <ticks>] [--battles <n>] [--seed <seed>]";
+
+struct Setup {
+    wizards: Vec<Vec<Spell>>,
+    enemies: Vec<(MonsterType, u8)>,
+    bosses: Vec<(MonsterType, u8)>,
+    waves: Vec<Wave>,
+    variance: bool,
+    tick_limit: u16,
+    stalemate_ticks: u16,
+    battles: u64,
+    seed: u64,
+}
+
+#[derive(Default)]
+struct Report {
+    battles: u64,
+    victories: u64,
+    defeats: u64,
+    retreats: u64,
+    draws: u64,
+    ticks: u64,
+    out_of_mana: u64,
+    crits: u64,
+    misses: u64,
+    immune: u64,
+    phase_changes: u64,
+    damage_dealt: HashMap<Glyph, u64>,
+    damage_taken: HashMap<Glyph, u64>,
+    spells_cast: HashMap<&'static str, u64>,
+    abilities_used: HashMap<&'static str, u64>,
+}
+
+impl Setup {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Setup, failure::Error> {
+        let mut setup = Setup {
+            wizards: Vec::new(),
+            enemies: Vec::new(),
+            bosses: Vec::new(),
+            waves: Vec::new(),
+            variance: true, // otherwise every seed plays out the same fight
+            tick_limit: TICK_LIMIT,
+            stalemate_ticks: STALEMATE_TICKS,
+            battles: 1000,
+            seed: 0,
+        };
+        while let Some(arg) = args.next() {
+            let value = args.next().ok_or_else(|| format_err!("{} needs a value\n{}", arg, USAGE))?;
+            match arg.as_str() {
+                "--wizard" => setup.wizards.push(value.split(',')
+                    .map(|name| spells::by_name(name.trim()).ok_or_else(|| format_err!("Unknown spell: {}", name)))
+                    .collect::<Result<Vec<Spell>, failure::Error>>()?),
+                "--enemy" => setup.enemies.push(Self::enemy(&value)?),
+                "--boss" => setup.bosses.push(Self::enemy(&value)?),
+                "--wave" => {
+                    let mut parts = value.splitn(2, ':');
+                    let tick = parts.next().unwrap_or_default().parse::<u16>()?;
+                    let enemy = Self::enemy(parts.next().unwrap_or_default())?;
+                    // Waves arriving on the same tick come in together.
+                    match setup.waves.iter_mut().find(|wave| wave.tick == tick) {
+                        Some(wave) => wave.monsters.push(enemy),
+                        None => setup.waves.push(Wave { tick, monsters: vec![enemy] }),
+                    }
+                }
+                "--variance" => setup.variance = match value.as_str() {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(format_err!("--variance is on or off\n{}", USAGE)),
+                },
+                "--tick-limit" => setup.tick_limit = value.parse()?,
+                "--stalemate" => setup.stalemate_ticks = value.parse()?,
+                "--battles" => setup.battles = value.parse()?,
+                "--seed" => setup.seed = value.parse()?,
+                _ => return Err(format_err!("Unknown argument: {}\n{}", arg, USAGE)),
+            }
+        }
+        if setup.wizards.is_empty() || (setup.enemies.is_empty() && setup.bosses.is_empty()) {
+            return Err(format_err!("Need at least one wizard and one enemy\n{}", USAGE));
+        }
+        Ok(setup)
+    }
+
+    fn enemy(value: &str) -> Result<(MonsterType, u8), failure::Error> {
+        let mut parts = value.splitn(2, ':');
+        let name = parts.next().unwrap_or_default();
+        let kind = MonsterType::from_name(name).ok_or_else(|| format_err!("Unknown monster type: {}", name))?;
+        let difficulty = match parts.next() {
+            Some(difficulty) => difficulty.parse::<u8>()?,
+            None => 1,
+        };
+        Ok((kind, difficulty))
+    }
+
+    fn simulate(&self, seed: u64) -> Report {
+        let mut col = Colosseum::headless(seed);
+        let allies = self.wizards.iter().enumerate()
+            .map(|(i, book)| {
+                let mut wizard = Wizard::new(format!("Wizard {}", i + 1));
+                for spell in book.iter() {
+                    wizard.add_spell_to_book(spell.clone());
+                }
+                col.insert(wizard)
+            })
+            .collect();
+        let mut enemies = self.enemies.iter().enumerate()
+            .map(|(i, (kind, difficulty))| col.insert(Monster::new(&format!("{} {}", kind.name(), i + 1), kind, *difficulty)))
+            .collect::<Vec<_>>();
+        for (kind, difficulty) in self.bosses.iter() {
+            enemies.push(col.insert(Monster::boss(kind.name(), kind, *difficulty)));
+        }
+        let mut battle = Battle::new(allies, enemies, seed)
+            .with_waves(self.waves.clone())
+            .with_tick_limit(self.tick_limit)
+            .with_stalemate_ticks(self.stalemate_ticks);
+        if self.variance {
+            battle = battle.with_variance();
+        }
+        let outcome = battle.run(&mut col);
+        Report::from_battle(&battle, &outcome)
+    }
+}
+
+impl Report {
+    fn from_battle(battle: &Battle, outcome: &BattleEvent) -> Report {
+        let mut report = Report {
+            battles: 1,
+            ticks: battle.past_ticks.len() as u64,
+            ..Report::default()
+        };
+        match outcome {
+            BattleEvent::Victory => report.victories += 1,
+            BattleEvent::Retreat => report.retreats += 1,
+            BattleEvent::Draw => report.draws += 1,
+            _ => report.defeats += 1,
+        }
+        for event in battle.past_ticks.iter().flatten() {
+            match event {
+                BattleEvent::Monster(BattleAtom::Mutation(BattleMut::Damage(_, _, damage, glyph))) =>
+                    *report.damage_dealt.entry(glyph.clone()).or_insert(0) += *damage as u64,
+                BattleEvent::Wizard(BattleAtom::Mutation(BattleMut::Damage(_, _, damage, glyph))) =>
+                    *report.damage_taken.entry(glyph.clone()).or_insert(0) += *damage as u64,
+                BattleEvent::Wizard(BattleAtom::CastSpell(_, spell)) =>
+                    *report.spells_cast.entry(spell.name).or_insert(0) += 1,
+                BattleEvent::Wizard(BattleAtom::OutOfMana(_)) => report.out_of_mana += 1,
+                BattleEvent::Wizard(BattleAtom::Crit(_, _)) | BattleEvent::Monster(BattleAtom::Crit(_, _)) => report.crits += 1,
+                BattleEvent::Wizard(BattleAtom::Miss(_, _)) | BattleEvent::Monster(BattleAtom::Miss(_, _)) => report.misses += 1,
+                BattleEvent::Wizard(BattleAtom::Immune(..)) | BattleEvent::Monster(BattleAtom::Immune(..)) => report.immune += 1,
+                BattleEvent::Monster(BattleAtom::PhaseChange(_, _)) => report.phase_changes += 1,
+                BattleEvent::Monster(BattleAtom::CastSpell(_, spell)) =>
+                    *report.abilities_used.entry(spell.name).or_insert(0) += 1,
+                _ => {}
+            }
+        }
+        report
+    }
+
+    fn merge(mut self, other: Report) -> Report {
+        self.battles += other.battles;
+        self.victories += other.victories;
+        self.defeats += other.defeats;
+        self.retreats += other.retreats;
+        self.draws += other.draws;
+        self.ticks += other.ticks;
+        self.out_of_mana += other.out_of_mana;
+        self.crits += other.crits;
+        self.misses += other.misses;
+        self.immune += other.immune;
+        self.phase_changes += other.phase_changes;
+        Self::merge_counts(&mut self.damage_dealt, other.damage_dealt);
+        Self::merge_counts(&mut self.damage_taken, other.damage_taken);
+        Self::merge_counts(&mut self.spells_cast, other.spells_cast);
+        Self::merge_counts(&mut self.abilities_used, other.abilities_used);
+        self
+    }
+
+    fn merge_counts<K>(into: &mut HashMap<K, u64>, from: HashMap<K, u64>) where K: std::hash::Hash + Eq {
+        for (key, count) in from.into_iter() {
+            *into.entry(key).or_insert(0) += count;
+        }
+    }
+
+    fn print(&self) {
+        let battles = self.battles.max(1) as f64;
+        println!("battles:   {}", self.battles);
+        println!("win rate:  {:.1}%", 100.0 * self.victories as f64 / battles);
+        println!("defeats:   {:.1}%", 100.0 * self.defeats as f64 / battles);
+        println!("retreats:  {:.1}%", 100.0 * self.retreats as f64 / battles);
+        println!("draws:     {:.1}%", 100.0 * self.draws as f64 / battles);
+        println!("avg ticks: {:.1}", self.ticks as f64 / battles);
+        println!("no mana:   {:.1} turns per battle", self.out_of_mana as f64 / battles);
+        println!("crits:     {:.1} per battle", self.crits as f64 / battles);
+        println!("misses:    {:.1} per battle", self.misses as f64 / battles);
+        println!("immune:    {:.1} per battle", self.immune as f64 / battles);
+        println!("phases:    {:.1} boss phase changes per battle", self.phase_changes as f64 / battles);
+        Self::print_counts("damage dealt by glyph", self.damage_dealt.iter().map(|(glyph, count)| (format!("{:?}", glyph), *count)), battles);
+        Self::print_counts("damage taken by glyph", self.damage_taken.iter().map(|(glyph, count)| (format!("{:?}", glyph), *count)), battles);
+        Self::print_counts("wizard spells cast", self.spells_cast.iter().map(|(name, count)| (name.to_string(), *count)), battles);
+        Self::print_counts("monster abilities used", self.abilities_used.iter().map(|(name, count)| (name.to_string(), *count)), battles);
+    }
+
+    fn print_counts(title: &str, counts: impl Iterator<Item = (String, u64)>, battles: f64) {
+        println!("{} (total / per battle):", title);
+        let mut counts = counts.collect::<Vec<(String, u64)>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        for (key, count) in counts.iter() {
+            println!("  {:<24} {:>10} {:>10.2}", key, count, *count as f64 / battles);
+        }
+    }
+}
+
+pub fn main() -> Result<(), failure::Error> {
+    mon::load()?;
+    let setup = Setup::from_args(std::env::args().skip(1))?;
+    let report = (0..setup.battles)
+        .into_par_iter()
+        .map(|i| setup.simulate(setup.seed.wrapping_add(i)))
+        .reduce(Report::default, Report::merge);
+    report.print();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(str::to_string)
+    }
+
+    #[test]
+    fn the_command_line_sets_up_the_fight() {
+        let setup = Setup::from_args(args("--wizard fireball,Heal --enemy Orc:2 --enemy Goblin --wave 5:Rat --wave 5:Bat:3 --battles 10")).unwrap();
+        assert_eq!(setup.wizards.len(), 1);
+        assert_eq!(setup.wizards[0].iter().map(|spell| spell.name).collect::<Vec<&str>>(), vec!["fireball", "Heal"]);
+        assert!(setup.enemies == vec![(MonsterType::Orc, 2), (MonsterType::Goblin, 1)]);
+        assert_eq!(setup.waves.len(), 1);
+        assert!(setup.waves[0].monsters == vec![(MonsterType::Rat, 1), (MonsterType::Bat, 3)]);
+        assert_eq!(setup.battles, 10);
+        assert!(setup.variance);
+        assert!(!Setup::from_args(args("--wizard fireball --enemy Orc --variance off")).unwrap().variance);
+    }
+
+    #[test]
+    fn a_bad_command_line_is_an_error() {
+        assert!(Setup::from_args(args("--wizard fireball")).is_err());
+        assert!(Setup::from_args(args("--wizard nothing --enemy Orc")).is_err());
+        assert!(Setup::from_args(args("--wizard fireball --enemy Nobody")).is_err());
+        assert!(Setup::from_args(args("--wizard fireball --enemy Orc --battles")).is_err());
+    }
+
+    #[test]
+    fn reports_add_up_over_battles() {
+        mon::load().unwrap();
+        let setup = Setup::from_args(args("--wizard fireball --enemy Goblin --battles 4")).unwrap();
+        let report = (0..setup.battles)
+            .map(|i| setup.simulate(i))
+            .fold(Report::default(), Report::merge);
+        assert_eq!(report.battles, 4);
+        assert_eq!(report.victories + report.defeats + report.retreats + report.draws, 4);
+        assert!(report.spells_cast["fireball"] > 0);
+    }
+}
@@ -0,0 +1,12 @@
+extern crate generational_arena;
+extern crate log;
+extern crate rand;
+
+// The headless half of the game: everything a battle, a quest or a save needs,
+// without a window or a GL context. The game itself adds the rest of `a` on top.
+pub mod a {
+    pub mod c;
+    pub mod q;
+    pub mod realms;
+    pub mod rng;
+}